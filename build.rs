@@ -3,6 +3,7 @@ const TRUSTED_SETUP_FILE: &str = include_str!("src/trusted_setup.txt");
 include!("src/enums.rs");
 include!("src/consts.rs");
 include!("src/pairings.rs");
+include!("src/setup_encoding.rs");
 
 #[cfg(not(any(target_arch = "riscv32", doc)))]
 fn main() {
@@ -41,16 +42,14 @@ fn main() {
                     array
                 })
                 .collect();
-        let _g2_points: Vec<[u8; BYTES_PER_G2_POINT]> =
-            hex_to_bytes(&trusted_setup_file[g1_points_idx..g2_points_idx].join(""))
-                .unwrap()
-                .chunks_exact(BYTES_PER_G2_POINT)
-                .map(|chunk| {
-                    let mut array = [0u8; BYTES_PER_G2_POINT];
-                    array.copy_from_slice(chunk);
-                    array
-                })
-                .collect();
+        // G2 lines may be compressed or uncompressed, so they are decoded one by one
+        let _g2_points: Vec<G2Affine> = trusted_setup_file[g1_points_idx..g2_points_idx]
+            .iter()
+            .map(|line| {
+                g2_from_setup_bytes(&hex_to_bytes(line).unwrap())
+                    .expect("load_trusted_setup Invalid g2 bytes")
+            })
+            .collect();
 
         assert_eq!(_g1_points.len(), num_g1_points);
         assert_eq!(_g2_points.len(), num_g2_points);
@@ -69,10 +68,7 @@ fn main() {
                 .expect("load_trusted_setup Invalid g1 bytes");
         });
 
-        _g2_points.iter().enumerate().for_each(|(i, bytes)| {
-            g2_points[i] = G2Affine::from_compressed_unchecked(bytes)
-                .expect("load_trusted_setup Invalid g2 bytes");
-        });
+        g2_points.copy_from_slice(&_g2_points);
 
         let _ = is_trusted_setup_in_lagrange_form(&g1_points, &g2_points);
 
@@ -191,6 +187,10 @@ fn main() {
     let mut roots_of_unity_bytes: Vec<u8> = Vec::new();
     let mut g1_bytes: Vec<u8> = Vec::new();
     let mut g2_bytes: Vec<u8> = Vec::new();
+    // Canonical encodings, decoded without `unsafe` unless the `unsafe-opt` feature is enabled
+    let mut roots_of_unity_le_bytes: Vec<u8> = Vec::new();
+    let mut g1_uncompressed_bytes: Vec<u8> = Vec::new();
+    let mut g2_uncompressed_bytes: Vec<u8> = Vec::new();
 
     roots_of_unity.iter().for_each(|&v| {
         roots_of_unity_bytes
@@ -205,6 +205,24 @@ fn main() {
         g2_bytes.extend_from_slice(unsafe { &std::mem::transmute::<G2Affine, [u8; 200]>(v) });
     });
 
+    roots_of_unity
+        .iter()
+        .for_each(|v| roots_of_unity_le_bytes.extend_from_slice(&v.to_bytes()));
+    g1_points
+        .iter()
+        .for_each(|v| g1_uncompressed_bytes.extend_from_slice(&v.to_uncompressed()));
+    g2_points
+        .iter()
+        .for_each(|v| g2_uncompressed_bytes.extend_from_slice(&v.to_uncompressed()));
+
+    for (path, bytes) in [
+        ("roots_of_unity_le.bin", &roots_of_unity_le_bytes),
+        ("g1_uncompressed.bin", &g1_uncompressed_bytes),
+        ("g2_uncompressed.bin", &g2_uncompressed_bytes),
+    ] {
+        fs::write(Path::new(&out_dir).join(path), bytes).unwrap();
+    }
+
     let mut roots_of_unity_file = fs::OpenOptions::new()
         .create(true)
         .truncate(true)
@@ -0,0 +1,86 @@
+//! Feeds arbitrary bytes to every public parser. Each of them must return `Err` on bad input,
+//! a panic is a bug.
+#![no_main]
+
+use kzg_rs::{
+    kzg_proof::{is_valid_commitment, parse_field_element, safe_g1_affine_from_bytes},
+    safe_g2_affine_from_bytes,
+    setup_encoding::g2_from_setup_bytes,
+    Blob, Bytes32, Bytes48, Bytes96, Cell, Endianness, KzgCommitment, KzgProof, KzgSettings,
+    BYTES_PER_BLOB,
+};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let _ = Bytes32::from_slice(data);
+    let _ = Bytes48::from_slice(data);
+    let _ = Bytes96::from_slice(data);
+    let _ = Blob::from_slice(data);
+    let _ = Cell::from_slice(data);
+    let _ = g2_from_setup_bytes(data);
+
+    // Setup loaders, the bytes split in two for the binary one
+    let (g1_bytes, g2_bytes) = data.split_at(data.len() / 2);
+    let _ = KzgSettings::load_trusted_setup(g1_bytes, g2_bytes);
+
+    if let Ok(text) = core::str::from_utf8(data) {
+        let _ = KzgSettings::parse_trusted_setup(text);
+        let _ = Bytes32::from_hex(text);
+        let _ = Bytes48::from_hex(text);
+        let _ = Bytes96::from_hex(text);
+        let _ = Blob::from_hex(text);
+        let _ = Cell::from_hex(text);
+    }
+
+    if let Some(bytes) = data.get(..32) {
+        let bytes = Bytes32::from_slice(bytes).unwrap();
+        let _ = parse_field_element(&bytes, Endianness::Big);
+        let _ = parse_field_element(&bytes, Endianness::Little);
+    }
+
+    if let Some(bytes) = data.get(..48) {
+        let bytes = Bytes48::from_slice(bytes).unwrap();
+        let _ = safe_g1_affine_from_bytes(&bytes);
+        let _ = is_valid_commitment(&bytes);
+        let commitment = KzgCommitment::from(bytes);
+        let _ = commitment.to_g1();
+        let _ = commitment.try_neg();
+    }
+
+    if let Some(bytes) = data.get(..96) {
+        let _ = safe_g2_affine_from_bytes(&Bytes96::from_slice(bytes).unwrap());
+    }
+
+    let kzg_settings = KzgSettings::load_trusted_setup_file().unwrap();
+
+    // commitment || z || y || proof
+    if let Some(bytes) = data.get(..160) {
+        let commitment = Bytes48::from_slice(&bytes[..48]).unwrap();
+        let z = Bytes32::from_slice(&bytes[48..80]).unwrap();
+        let y = Bytes32::from_slice(&bytes[80..112]).unwrap();
+        let proof = Bytes48::from_slice(&bytes[112..160]).unwrap();
+        let _ = KzgProof::verify_kzg_proof(&commitment, &z, &y, &proof, &kzg_settings);
+    }
+
+    // Blob parsing, with the input repeated to fill a whole blob
+    if !data.is_empty() {
+        let bytes = data
+            .iter()
+            .copied()
+            .cycle()
+            .take(BYTES_PER_BLOB)
+            .collect::<Vec<_>>();
+        let blob = Blob::from_slice(&bytes).unwrap();
+        let _ = blob.as_polynomial();
+        let _ = blob.as_polynomial_with_endianness(Endianness::Little);
+        let _ = Blob::from_field_element_bytes(&blob.to_field_element_bytes());
+    }
+
+    // Mismatched batch lengths, driven by the first byte
+    if let Some(&lengths) = data.first() {
+        let blobs = vec![Blob::zero(); usize::from(lengths & 0b11)];
+        let commitments = vec![Bytes48::from([0u8; 48]); usize::from((lengths >> 2) & 0b11)];
+        let proofs = vec![Bytes48::from([0u8; 48]); usize::from((lengths >> 4) & 0b11)];
+        let _ = KzgProof::verify_blob_kzg_proof_batch(blobs, commitments, proofs, &kzg_settings);
+    }
+});
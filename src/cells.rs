@@ -0,0 +1,465 @@
+use crate::{
+    dtypes::{Blob, Bytes48, Cell},
+    enums::KzgError,
+    fft::{
+        bit_reversal_permutation, coefficients_to_evaluations, domain, fft_unchecked,
+        ifft_unchecked,
+    },
+    kzg_proof::{
+        compute_challenge_with_domain, g1_affine_from_bytes_with_policy, poly_to_kzg_commitment,
+        scalar_to_blob_bytes,
+    },
+    trusted_setup::{get_g1_points, KzgSettings},
+    CELLS_PER_EXT_BLOB, FIELD_ELEMENTS_PER_CELL, FIELD_ELEMENTS_PER_EXT_BLOB,
+    NUM_FIELD_ELEMENTS_PER_BLOB,
+};
+
+use alloc::vec::Vec;
+use bls12_381::{G1Affine, G1Projective, Scalar};
+use spin::Once;
+
+/// Whether the cells at hand are enough to recover the extended blob, see [`recovery_status`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecoveryStatus {
+    /// At least half of the `CELLS_PER_EXT_BLOB` cells are present
+    pub recoverable: bool,
+    /// Indices of the absent cells, in ascending order
+    pub missing: Vec<u64>,
+}
+
+/// Checks whether the cells at `cell_indices` suffice to recover the extended blob, without
+/// running the recovery itself. Lets a sampler decide whether it needs to fetch more cells.
+/// Rejects the indices a recovery would, with [`KzgError::CellIndexOutOfRange`] and
+/// [`KzgError::DuplicateCellIndex`].
+pub fn recovery_status(cell_indices: &[u64]) -> Result<RecoveryStatus, KzgError> {
+    let mut present = [false; CELLS_PER_EXT_BLOB];
+    for &index in cell_indices {
+        let slot = usize::try_from(index)
+            .ok()
+            .and_then(|index| present.get_mut(index))
+            .ok_or(KzgError::CellIndexOutOfRange { index })?;
+        if *slot {
+            return Err(KzgError::DuplicateCellIndex { index });
+        }
+        *slot = true;
+    }
+
+    let missing = (0..CELLS_PER_EXT_BLOB as u64)
+        .filter(|&index| !present[index as usize])
+        .collect::<Vec<_>>();
+
+    Ok(RecoveryStatus {
+        recoverable: cell_indices.len() * 2 >= CELLS_PER_EXT_BLOB,
+        missing,
+    })
+}
+
+/// Computes the [`CELLS_PER_EXT_BLOB`] cells of the extended blob, as in EIP-7594, without their
+/// proofs. Cell `i` holds the evaluations over the `i`-th coset of the bit-reversed extended
+/// domain.
+pub fn compute_cells(blob: &Blob, kzg_settings: &KzgSettings) -> Result<Vec<Cell>, KzgError> {
+    let (evaluations, _) = blob.to_evaluations(kzg_settings)?;
+    compute_cells_from_polynomial(&evaluations, kzg_settings)
+}
+
+/// Same as [`compute_cells`] for a blob already parsed into its evaluations, in the bit-reversed
+/// order of the blob as returned by [`Blob::to_evaluations`]
+pub fn compute_cells_from_polynomial(
+    evaluations: &[Scalar],
+    kzg_settings: &KzgSettings,
+) -> Result<Vec<Cell>, KzgError> {
+    if evaluations.len() != NUM_FIELD_ELEMENTS_PER_BLOB {
+        return Err(KzgError::MismatchedLengths {
+            expected: NUM_FIELD_ELEMENTS_PER_BLOB,
+            got: evaluations.len(),
+        });
+    }
+    if kzg_settings.field_elements_per_blob() != NUM_FIELD_ELEMENTS_PER_BLOB {
+        return Err(KzgError::BadArgs(format!(
+            "The setup domain has {} points, but a blob has {} field elements",
+            kzg_settings.field_elements_per_blob(),
+            NUM_FIELD_ELEMENTS_PER_BLOB
+        )));
+    }
+
+    let coefficients = evaluations_to_coefficients(evaluations.to_vec(), kzg_settings);
+    extended_cells(&coefficients, kzg_settings)
+}
+
+/// Computes the [`CELLS_PER_EXT_BLOB`] cells of the extended blob and the KZG proof of each one,
+/// as in EIP-7594. Cell `i` holds the evaluations over the `i`-th coset of the bit-reversed
+/// extended domain, and its proof opens the blob polynomial on that whole coset.
+pub fn compute_cells_and_kzg_proofs(
+    blob: &Blob,
+    kzg_settings: &KzgSettings,
+) -> Result<(Vec<Cell>, Vec<Bytes48>), KzgError> {
+    let coefficients = blob_to_coefficients(blob, kzg_settings)?;
+    cells_and_proofs(&coefficients, kzg_settings)
+}
+
+/// Computes the proof of [`compute_blob_kzg_proof`](crate::compute_blob_kzg_proof) along with
+/// the cells and proofs of [`compute_cells_and_kzg_proofs`], converting the blob to coefficient
+/// form once for both
+pub fn compute_blob_and_cell_proofs(
+    blob: &Blob,
+    commitment_bytes: &Bytes48,
+    kzg_settings: &KzgSettings,
+) -> Result<(Bytes48, Vec<Cell>, Vec<Bytes48>), KzgError> {
+    let commitment =
+        g1_affine_from_bytes_with_policy(commitment_bytes, kzg_settings.subgroup_policy)
+            .map_err(|e| e.with_context("The commitment is not a valid G1 point"))?;
+    let coefficients = blob_to_coefficients(blob, kzg_settings)?;
+
+    // (p(X) - p(z)) / (X - z) by synthetic division, the remainder being p(z) itself
+    let z = compute_challenge_with_domain(blob, &commitment, kzg_settings.fiat_shamir_domain)?;
+    let mut quotient = vec![Scalar::zero(); coefficients.len()];
+    for i in (1..coefficients.len()).rev() {
+        quotient[i - 1] = coefficients[i] + z * quotient[i];
+    }
+    let blob_proof = commit_coefficients(quotient, kzg_settings)?;
+
+    let (cells, cell_proofs) = cells_and_proofs(&coefficients, kzg_settings)?;
+    Ok((blob_proof, cells, cell_proofs))
+}
+
+/// Parses the blob and interpolates it into the coefficients of its polynomial
+fn blob_to_coefficients(blob: &Blob, kzg_settings: &KzgSettings) -> Result<Vec<Scalar>, KzgError> {
+    let (evaluations, _) = blob.to_evaluations(kzg_settings)?;
+    Ok(evaluations_to_coefficients(evaluations, kzg_settings))
+}
+
+/// Interpolates the bit-reversed evaluations over the blob domain into coefficients
+fn evaluations_to_coefficients(
+    mut evaluations: Vec<Scalar>,
+    kzg_settings: &KzgSettings,
+) -> Vec<Scalar> {
+    bit_reversal_permutation(&mut evaluations);
+    ifft_unchecked(
+        &mut evaluations,
+        &domain(kzg_settings.field_elements_per_blob()),
+    );
+    evaluations
+}
+
+/// Commits to a polynomial of at most [`NUM_FIELD_ELEMENTS_PER_BLOB`] coefficients. The setup
+/// only has Lagrange points, so it goes through the evaluations over the blob domain.
+fn commit_coefficients(
+    coefficients: Vec<Scalar>,
+    kzg_settings: &KzgSettings,
+) -> Result<Bytes48, KzgError> {
+    let evaluations =
+        coefficients_to_evaluations(coefficients, kzg_settings.field_elements_per_blob());
+    Ok(poly_to_kzg_commitment(&evaluations, kzg_settings)?
+        .to_compressed()
+        .into())
+}
+
+/// Evaluates the polynomial over the extended domain for the cells, and opens it on the coset
+/// of each cell
+fn cells_and_proofs(
+    coefficients: &[Scalar],
+    kzg_settings: &KzgSettings,
+) -> Result<(Vec<Cell>, Vec<Bytes48>), KzgError> {
+    let cells = extended_cells(coefficients, kzg_settings)?;
+    let proofs =
+        with_cell_proof_columns(kzg_settings, |columns| cell_proofs(coefficients, columns));
+
+    Ok((cells, proofs))
+}
+
+/// Evaluates the polynomial over the extended domain and splits the evaluations into cells
+fn extended_cells(
+    coefficients: &[Scalar],
+    kzg_settings: &KzgSettings,
+) -> Result<Vec<Cell>, KzgError> {
+    let mut extended = coefficients.to_vec();
+    extended.resize(FIELD_ELEMENTS_PER_EXT_BLOB, Scalar::zero());
+    fft_unchecked(&mut extended, &domain(FIELD_ELEMENTS_PER_EXT_BLOB));
+    bit_reversal_permutation(&mut extended);
+
+    extended
+        .chunks_exact(FIELD_ELEMENTS_PER_CELL)
+        .map(|evaluations| {
+            let bytes = evaluations
+                .iter()
+                .flat_map(|evaluation| {
+                    <[u8; 32]>::from(scalar_to_blob_bytes(
+                        evaluation,
+                        kzg_settings.field_element_endianness,
+                    ))
+                })
+                .collect::<Vec<_>>();
+            Cell::from_slice(&bytes)
+        })
+        .collect()
+}
+
+/// Opens the polynomial on every cell coset with FK20. The quotient of p by X^64 - s is
+/// sum_d s^d * (p >> 64(d + 1)), so its commitments Y_d are shared by all cells, and the proofs
+/// are the evaluations of sum_d Y_d X^d at the `CELLS_PER_EXT_BLOB` values of s.
+fn cell_proofs(coefficients: &[Scalar], columns: &[Vec<G1Projective>]) -> Vec<Bytes48> {
+    let rows = coefficients.len() / FIELD_ELEMENTS_PER_CELL;
+    let roots = domain(2 * rows);
+
+    // Each Y_d sums a Toeplitz product per column offset, done as a circular convolution of
+    // size 2 * rows. The convolutions are linear, so they share a single inverse FFT.
+    let mut products = vec![G1Projective::identity(); 2 * rows];
+    for (offset, column) in columns.iter().enumerate() {
+        let mut row = vec![Scalar::zero(); 2 * rows];
+        for (m, value) in row.iter_mut().take(rows).enumerate() {
+            *value = coefficients[offset + FIELD_ELEMENTS_PER_CELL * m];
+        }
+        fft_unchecked(&mut row, &roots);
+        for ((product, point), scalar) in products.iter_mut().zip(column).zip(&row) {
+            *product += point * scalar;
+        }
+    }
+    count_ops!(g1_scalar_muls += columns.len() * 2 * rows);
+    ifft_unchecked(&mut products, &roots);
+
+    // Y_d lands at index d + 1, and s runs over the bit-reversed roots of order CELLS_PER_EXT_BLOB
+    let mut proofs = vec![G1Projective::identity(); CELLS_PER_EXT_BLOB];
+    for (proof, product) in proofs.iter_mut().zip(&products[1..rows]) {
+        *proof = *product;
+    }
+    fft_unchecked(&mut proofs, &domain(CELLS_PER_EXT_BLOB));
+    bit_reversal_permutation(&mut proofs);
+
+    let mut affine = vec![G1Affine::identity(); CELLS_PER_EXT_BLOB];
+    G1Projective::batch_normalize(&proofs, &mut affine);
+    affine
+        .iter()
+        .map(|proof| proof.to_compressed().into())
+        .collect()
+}
+
+/// Runs `f` with the FFTs of the FK20 Toeplitz columns of the setup. They cost far more than a
+/// single proof computation, so those of the built-in setup are computed once and kept.
+fn with_cell_proof_columns<T>(
+    kzg_settings: &KzgSettings,
+    f: impl FnOnce(&[Vec<G1Projective>]) -> T,
+) -> T {
+    static BUILT_IN: Once<Vec<Vec<G1Projective>>> = Once::new();
+
+    if core::ptr::eq(kzg_settings.g1_points, get_g1_points()) {
+        f(BUILT_IN.call_once(|| cell_proof_columns(kzg_settings)))
+    } else {
+        f(&cell_proof_columns(kzg_settings))
+    }
+}
+
+/// For every offset `a` within a cell, the FFT of the column [tau^(a + 64b)]G1 laid out for a
+/// circular convolution of size 2 * rows: entry 0 for b = 0, then entry 2 * rows - b for b > 0
+fn cell_proof_columns(kzg_settings: &KzgSettings) -> Vec<Vec<G1Projective>> {
+    // The setup holds [L_j(tau)]G1 in bit-reversed order, and tau^i = sum_j w^(ij) L_j(tau)
+    let mut monomial = kzg_settings
+        .g1_points
+        .iter()
+        .map(G1Projective::from)
+        .collect::<Vec<_>>();
+    bit_reversal_permutation(&mut monomial);
+    fft_unchecked(
+        &mut monomial,
+        &domain(kzg_settings.field_elements_per_blob()),
+    );
+
+    let rows = monomial.len() / FIELD_ELEMENTS_PER_CELL;
+    let roots = domain(2 * rows);
+    (0..FIELD_ELEMENTS_PER_CELL)
+        .map(|offset| {
+            let mut column = vec![G1Projective::identity(); 2 * rows];
+            column[0] = monomial[offset];
+            for b in 1..rows {
+                column[2 * rows - b] = monomial[offset + FIELD_ELEMENTS_PER_CELL * b];
+            }
+            fft_unchecked(&mut column, &roots);
+            column
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        compute_blob_kzg_proof, dtypes::Polynomial, fft::bit_reversed_index,
+        kzg_proof::safe_g1_affine_from_bytes, pairings_verify, trusted_setup::domain_generator,
+        Bytes32, Endianness,
+    };
+    use bls12_381::G2Affine;
+
+    fn test_blob() -> Blob {
+        let field_elements = (0..NUM_FIELD_ELEMENTS_PER_BLOB as u64)
+            .map(|i| scalar_to_blob_bytes(&Scalar::from(i * i + 7), Endianness::Big))
+            .collect::<Vec<Bytes32>>();
+        Blob::from_field_element_bytes(&field_elements).unwrap()
+    }
+
+    #[test]
+    fn test_compute_cells_and_kzg_proofs() {
+        let kzg_settings = KzgSettings::load_trusted_setup_file().unwrap();
+        let blob = test_blob();
+        let coefficients = blob_to_coefficients(&blob, &kzg_settings).unwrap();
+        let polynomial = Polynomial::try_from(coefficients.as_slice()).unwrap();
+        let commitment = polynomial.commit(&kzg_settings).unwrap().to_g1().unwrap();
+
+        let (cells, proofs) = compute_cells_and_kzg_proofs(&blob, &kzg_settings).unwrap();
+        assert_eq!(cells.len(), CELLS_PER_EXT_BLOB);
+        assert_eq!(proofs.len(), CELLS_PER_EXT_BLOB);
+
+        // The first half of the cells lies on the blob domain, the second half off it
+        let extended_generator =
+            domain_generator(FIELD_ELEMENTS_PER_EXT_BLOB.trailing_zeros() as usize);
+        for cell_index in [0, 1, 63, 64, 127] {
+            let cell = &cells[cell_index];
+            let proof = safe_g1_affine_from_bytes(&proofs[cell_index]).unwrap();
+            let shift = extended_generator.pow_vartime(&[
+                bit_reversed_index(cell_index, CELLS_PER_EXT_BLOB) as u64,
+                0,
+                0,
+                0,
+            ]);
+            let coset_generator =
+                domain_generator(FIELD_ELEMENTS_PER_CELL.trailing_zeros() as usize);
+
+            for (j, bytes) in cell.as_slice().chunks_exact(32).enumerate() {
+                let x = shift
+                    * coset_generator.pow_vartime(&[
+                        bit_reversed_index(j, FIELD_ELEMENTS_PER_CELL) as u64,
+                        0,
+                        0,
+                        0,
+                    ]);
+                assert_eq!(
+                    bytes,
+                    scalar_to_blob_bytes(&polynomial.evaluate(&x), Endianness::Big).as_slice()
+                );
+            }
+
+            // e(C - [I(tau)], G2) = e(proof, [tau^64 - h^64]G2), with I the remainder of p
+            // modulo the vanishing polynomial X^64 - h^64 of the coset
+            let vanishing_constant = shift.pow_vartime(&[FIELD_ELEMENTS_PER_CELL as u64, 0, 0, 0]);
+            let mut remainder = vec![Scalar::zero(); FIELD_ELEMENTS_PER_CELL];
+            for (i, coefficient) in coefficients.iter().enumerate().rev() {
+                let j = i % FIELD_ELEMENTS_PER_CELL;
+                remainder[j] = remainder[j] * vanishing_constant + coefficient;
+            }
+            let interpolant = Polynomial::try_from(remainder.as_slice())
+                .unwrap()
+                .commit(&kzg_settings)
+                .unwrap()
+                .to_g1()
+                .unwrap();
+            let vanishing = kzg_settings.g2_points[FIELD_ELEMENTS_PER_CELL]
+                - G2Affine::generator() * vanishing_constant;
+            assert!(pairings_verify(
+                (G1Projective::from(commitment) - interpolant).into(),
+                G2Affine::generator(),
+                proof,
+                vanishing.into()
+            ));
+            assert!(!pairings_verify(
+                (G1Projective::from(commitment) - interpolant).into(),
+                G2Affine::generator(),
+                G1Affine::generator(),
+                vanishing.into()
+            ));
+        }
+    }
+
+    #[test]
+    fn test_compute_cells_from_polynomial() {
+        let kzg_settings = KzgSettings::load_trusted_setup_file().unwrap();
+        let blob = test_blob();
+        let (evaluations, _) = blob.to_evaluations(&kzg_settings).unwrap();
+
+        let cells = compute_cells(&blob, &kzg_settings).unwrap();
+        assert_eq!(cells.len(), CELLS_PER_EXT_BLOB);
+        assert_eq!(
+            cells,
+            compute_cells_from_polynomial(&evaluations, &kzg_settings).unwrap()
+        );
+
+        // The first half of the bit-reversed extended domain is the blob domain, in blob order
+        let blob_half = cells[..CELLS_PER_EXT_BLOB / 2]
+            .iter()
+            .flat_map(|cell| cell.as_slice().to_vec())
+            .collect::<Vec<_>>();
+        assert_eq!(blob_half, blob.as_slice());
+
+        assert!(matches!(
+            compute_cells_from_polynomial(&evaluations[1..], &kzg_settings),
+            Err(KzgError::MismatchedLengths {
+                expected: NUM_FIELD_ELEMENTS_PER_BLOB,
+                got
+            }) if got == NUM_FIELD_ELEMENTS_PER_BLOB - 1
+        ));
+    }
+
+    #[test]
+    fn test_compute_blob_and_cell_proofs() {
+        let kzg_settings = KzgSettings::load_trusted_setup_file().unwrap();
+        let blob = test_blob();
+        let commitment = crate::blob_to_kzg_commitment(&blob, &kzg_settings).unwrap();
+
+        let (blob_proof, cells, cell_proofs) =
+            compute_blob_and_cell_proofs(&blob, &commitment, &kzg_settings).unwrap();
+        assert_eq!(
+            blob_proof,
+            compute_blob_kzg_proof(&blob, &commitment, &kzg_settings).unwrap()
+        );
+        let (expected_cells, expected_proofs) =
+            compute_cells_and_kzg_proofs(&blob, &kzg_settings).unwrap();
+        assert_eq!(cells, expected_cells);
+        assert_eq!(cell_proofs, expected_proofs);
+
+        let mut bad_commitment = [0u8; 48];
+        bad_commitment[0] = 0x80;
+        bad_commitment[47] = 1;
+        assert!(matches!(
+            compute_blob_and_cell_proofs(&blob, &bad_commitment.into(), &kzg_settings),
+            Err(KzgError::NotOnCurve(_))
+        ));
+    }
+
+    #[test]
+    fn test_recovery_status() {
+        let half = (0..CELLS_PER_EXT_BLOB as u64)
+            .step_by(2)
+            .collect::<Vec<_>>();
+        let status = recovery_status(&half).unwrap();
+        assert!(status.recoverable);
+        assert_eq!(
+            status.missing,
+            (1..CELLS_PER_EXT_BLOB as u64)
+                .step_by(2)
+                .collect::<Vec<_>>()
+        );
+
+        let status = recovery_status(&half[1..]).unwrap();
+        assert!(!status.recoverable);
+        assert_eq!(status.missing.len(), CELLS_PER_EXT_BLOB / 2 + 1);
+        assert_eq!(status.missing[..2], [0, 1]);
+    }
+
+    #[test]
+    fn test_recovery_status_rejects_duplicate_index() {
+        assert!(matches!(
+            recovery_status(&[3, 7, 3]),
+            Err(KzgError::DuplicateCellIndex { index: 3 })
+        ));
+    }
+
+    #[test]
+    fn test_recovery_status_rejects_out_of_range_index() {
+        let index = CELLS_PER_EXT_BLOB as u64;
+        assert!(matches!(
+            recovery_status(&[0, index]),
+            Err(KzgError::CellIndexOutOfRange { index: i }) if i == index
+        ));
+        assert!(matches!(
+            recovery_status(&[u64::MAX]),
+            Err(KzgError::CellIndexOutOfRange { index: u64::MAX })
+        ));
+    }
+}
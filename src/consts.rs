@@ -8,12 +8,25 @@ pub const NUM_FIELD_ELEMENTS_PER_BLOB: usize = 4096;
 pub const BYTES_PER_BLOB: usize = NUM_FIELD_ELEMENTS_PER_BLOB * BYTES_PER_FIELD_ELEMENT;
 pub const BYTES_PER_COMMITMENT: usize = 48;
 pub const BYTES_PER_PROOF: usize = 48;
+pub const FIELD_ELEMENTS_PER_EXT_BLOB: usize = 2 * NUM_FIELD_ELEMENTS_PER_BLOB;
+pub const FIELD_ELEMENTS_PER_CELL: usize = 64;
+pub const CELLS_PER_EXT_BLOB: usize = FIELD_ELEMENTS_PER_EXT_BLOB / FIELD_ELEMENTS_PER_CELL;
+pub const BYTES_PER_CELL: usize = FIELD_ELEMENTS_PER_CELL * BYTES_PER_FIELD_ELEMENT;
+/// First byte of the EIP-4844 versioned hash of a KZG commitment
+pub const VERSIONED_HASH_VERSION_KZG: u8 = 0x01;
 pub const DOMAIN_STR_LENGTH: usize = 16;
 pub const CHALLENGE_INPUT_SIZE: usize =
     DOMAIN_STR_LENGTH + 16 + BYTES_PER_BLOB + BYTES_PER_COMMITMENT;
 pub const FIAT_SHAMIR_PROTOCOL_DOMAIN: &str = "FSBLOBVERIFY_V1_";
 pub const RANDOM_CHALLENGE_KZG_BATCH_DOMAIN: &str = "RCKZGBATCH___V1_";
 
+// The challenge layouts hard-code 16-byte domain separators, a wrong length must fail the build
+const _: () = assert!(DOMAIN_STR_LENGTH == 16);
+const _: () = assert!(FIAT_SHAMIR_PROTOCOL_DOMAIN.len() == DOMAIN_STR_LENGTH);
+const _: () = assert!(RANDOM_CHALLENGE_KZG_BATCH_DOMAIN.len() == DOMAIN_STR_LENGTH);
+// The blob is the evaluation form over the FFT domain, so both must have the same size
+const _: () = assert!(NUM_ROOTS_OF_UNITY == NUM_FIELD_ELEMENTS_PER_BLOB);
+
 pub const SCALE2_ROOT_OF_UNITY: [[u64; 4]; 32] = [
     [
         0x0000000000000001,
@@ -217,3 +230,16 @@ pub const MODULUS: [u64; 4] = [
     0x3339_d808_09a1_d805,
     0x73ed_a753_299d_7d48,
 ];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_domain_str_length() {
+        assert_eq!(FIAT_SHAMIR_PROTOCOL_DOMAIN.len(), 16);
+        assert_eq!(RANDOM_CHALLENGE_KZG_BATCH_DOMAIN.len(), 16);
+        assert!(FIAT_SHAMIR_PROTOCOL_DOMAIN.is_ascii());
+        assert!(RANDOM_CHALLENGE_KZG_BATCH_DOMAIN.is_ascii());
+    }
+}
@@ -1,13 +1,31 @@
 use crate::enums::KzgError;
-use crate::kzg_proof::safe_scalar_affine_from_bytes;
-use crate::{BYTES_PER_BLOB, BYTES_PER_FIELD_ELEMENT};
+use crate::fft::coefficients_to_evaluations;
+use crate::kzg_proof::{
+    parse_field_element, poly_to_kzg_commitment, safe_g1_affine_from_bytes, scalar_to_blob_bytes,
+};
+use crate::trusted_setup::KzgSettings;
+use crate::{
+    BYTES_PER_BLOB, BYTES_PER_CELL, BYTES_PER_FIELD_ELEMENT, BYTES_PER_G2_POINT,
+    NUM_FIELD_ELEMENTS_PER_BLOB,
+};
 
-use alloc::{string::ToString, vec::Vec};
-use bls12_381::Scalar;
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+use bls12_381::{G1Affine, G1Projective, Scalar};
+use core::{
+    fmt,
+    iter::Sum,
+    ops::{Add, Neg},
+};
+use sha2::{Digest, Sha256};
 
 macro_rules! define_bytes_type {
-    ($name:ident, $size:expr) => {
-        #[derive(Debug, Clone)]
+    ($(#[$meta:meta])* $name:ident, $size:expr) => {
+        $(#[$meta])*
+        /// Compared and hashed by content, so it can key maps and caches directly
+        #[derive(Clone, PartialEq, Eq, Hash)]
         pub struct $name([u8; $size]);
 
         impl $name {
@@ -25,6 +43,26 @@ macro_rules! define_bytes_type {
             pub fn as_slice(&self) -> &[u8] {
                 &self.0
             }
+
+            /// Parses hex with an optional `0x` prefix, as found in block explorers and test vectors
+            pub fn from_hex(hex_str: &str) -> Result<Self, KzgError> {
+                let bytes = hex::decode(hex_str.strip_prefix("0x").unwrap_or(hex_str)).map_err(|e| {
+                    KzgError::InvalidHexFormat(format!("Failed to decode hex: {}", e))
+                })?;
+                if bytes.len() != $size {
+                    return Err(KzgError::InvalidBytesLength(format!(
+                        "Expected {} bytes, got {}",
+                        $size,
+                        bytes.len()
+                    )));
+                }
+                Self::from_slice(&bytes)
+            }
+
+            /// Lowercase hex with a `0x` prefix
+            pub fn to_hex(&self) -> String {
+                format!("0x{}", hex::encode(self.0))
+            }
         }
 
         impl From<$name> for [u8; $size] {
@@ -32,22 +70,453 @@ macro_rules! define_bytes_type {
                 value.0
             }
         }
+
+        impl From<[u8; $size]> for $name {
+            fn from(value: [u8; $size]) -> $name {
+                $name(value)
+            }
+        }
+
+        impl TryFrom<&[u8]> for $name {
+            type Error = KzgError;
+
+            fn try_from(slice: &[u8]) -> Result<Self, Self::Error> {
+                Self::from_slice(slice)
+            }
+        }
+
+        #[cfg(feature = "zeroize")]
+        impl zeroize::Zeroize for $name {
+            fn zeroize(&mut self) {
+                self.0.zeroize();
+            }
+        }
+
+        /// Only the final location is wiped: copies left behind by moves, e.g. into a `Box`, and
+        /// the arrays handed out by `From` are not
+        #[cfg(feature = "zeroize")]
+        impl Drop for $name {
+            fn drop(&mut self) {
+                zeroize::Zeroize::zeroize(self);
+            }
+        }
+
+        #[cfg(feature = "zeroize")]
+        impl zeroize::ZeroizeOnDrop for $name {}
+
+        /// `0x`-prefixed hex in human-readable formats, raw bytes otherwise
+        #[cfg(feature = "serde")]
+        impl serde::Serialize for $name {
+            fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                if serializer.is_human_readable() {
+                    serializer.serialize_str(&self.to_hex())
+                } else {
+                    serializer.serialize_bytes(&self.0)
+                }
+            }
+        }
+
+        #[cfg(feature = "serde")]
+        impl<'de> serde::Deserialize<'de> for $name {
+            fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                if deserializer.is_human_readable() {
+                    deserializer.deserialize_str(BytesVisitor(core::marker::PhantomData))
+                } else {
+                    deserializer.deserialize_bytes(BytesVisitor(core::marker::PhantomData))
+                }
+            }
+        }
     };
 }
 
-define_bytes_type!(Bytes32, 32);
-define_bytes_type!(Bytes48, 48);
+/// Reads a bytes type from either hex or raw bytes, see `define_bytes_type!`
+#[cfg(feature = "serde")]
+struct BytesVisitor<T>(core::marker::PhantomData<T>);
+
+#[cfg(feature = "serde")]
+impl<T: for<'a> TryFrom<&'a [u8], Error = KzgError>> serde::de::Visitor<'_> for BytesVisitor<T> {
+    type Value = T;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("hex-encoded or raw bytes")
+    }
+
+    fn visit_str<E: serde::de::Error>(self, value: &str) -> Result<T, E> {
+        let bytes = hex::decode(value.strip_prefix("0x").unwrap_or(value)).map_err(E::custom)?;
+        self.visit_bytes(&bytes)
+    }
+
+    fn visit_bytes<E: serde::de::Error>(self, value: &[u8]) -> Result<T, E> {
+        T::try_from(value).map_err(E::custom)
+    }
+}
+
+/// Byte order used to encode field elements inside a blob
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Endianness {
+    /// Big-endian, as mandated by EIP-4844
+    #[default]
+    Big,
+    /// Little-endian, for non-standard deployments
+    Little,
+}
+
+/// What to do with G1 inputs that are on the curve but outside the prime-order subgroup
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum SubgroupPolicy {
+    /// Reject them, as EIP-4844 requires
+    #[default]
+    Reject,
+    /// Multiply them by the cofactor and carry on.
+    ///
+    /// **INSECURE** for consensus: many encodings map to the same subgroup point, so a proof
+    /// accepted this way does not bind the bytes that were supplied, and results diverge from
+    /// every conforming implementation. Only meant for research on malformed inputs.
+    ClearCofactor,
+}
+
+define_bytes_type!(
+    #[derive(Debug)]
+    Bytes32,
+    32
+);
+define_bytes_type!(
+    #[derive(Debug)]
+    Bytes48,
+    48
+);
+define_bytes_type!(
+    /// A compressed G2 point
+    #[derive(Debug)]
+    Bytes96,
+    BYTES_PER_G2_POINT
+);
 define_bytes_type!(Blob, BYTES_PER_BLOB);
+define_bytes_type!(
+    /// [`FIELD_ELEMENTS_PER_CELL`](crate::FIELD_ELEMENTS_PER_CELL) evaluations of the extended
+    /// blob over one coset of the EIP-7594 domain
+    #[derive(Debug)]
+    Cell,
+    BYTES_PER_CELL
+);
+
+/// Prints the blob length and a short SHA-256 digest instead of dumping all its bytes
+impl fmt::Debug for Blob {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let digest = Sha256::digest(self.0);
+        f.debug_struct("Blob")
+            .field("len", &self.0.len())
+            .field("sha256", &format_args!("0x{}…", hex::encode(&digest[..8])))
+            .finish()
+    }
+}
 
 impl Blob {
+    /// The all-zero blob, committing to the point at infinity
+    pub fn zero() -> Self {
+        Blob([0u8; BYTES_PER_BLOB])
+    }
+
+    /// Copies the blob starting at byte `offset` out of a large buffer, typically a
+    /// memory-mapped file of concatenated blobs, without reading the rest of it
+    pub fn from_mmap(mmap: &[u8], offset: usize) -> Result<Self, KzgError> {
+        let window = offset
+            .checked_add(BYTES_PER_BLOB)
+            .and_then(|end| mmap.get(offset..end))
+            .ok_or_else(|| {
+                KzgError::InvalidBytesLength(format!(
+                    "No blob at offset {} of a {}-byte buffer",
+                    offset,
+                    mmap.len()
+                ))
+            })?;
+        Self::from_slice(window)
+    }
+
+    /// Copies `data` into a blob and zero-pads the rest. Longer inputs are rejected rather than
+    /// truncated. The bytes are not re-encoded, so every 32-byte chunk still has to be a
+    /// canonical field element for the blob to be committed to.
+    pub fn from_bytes_padded(data: &[u8]) -> Result<Self, KzgError> {
+        if data.len() > BYTES_PER_BLOB {
+            return Err(KzgError::InvalidBytesLength(format!(
+                "Expected at most {} bytes, got {}",
+                BYTES_PER_BLOB,
+                data.len()
+            )));
+        }
+        let mut blob = Self::zero();
+        blob.0[..data.len()].copy_from_slice(data);
+        Ok(blob)
+    }
+
     pub fn as_polynomial(&self) -> Result<Vec<Scalar>, KzgError> {
+        self.as_polynomial_with_endianness(Endianness::Big)
+    }
+
+    /// Parses the blob into its field elements, reading each one with the given byte order
+    pub fn as_polynomial_with_endianness(
+        &self,
+        endianness: Endianness,
+    ) -> Result<Vec<Scalar>, KzgError> {
         self.0
             .chunks(BYTES_PER_FIELD_ELEMENT)
             .map(|slice| {
-                Bytes32::from_slice(slice).and_then(|bytes| safe_scalar_affine_from_bytes(&bytes))
+                Bytes32::from_slice(slice).and_then(|bytes| parse_field_element(&bytes, endianness))
             })
             .collect()
     }
+
+    /// Parses the blob with the setup's byte order and returns its evaluations together with the
+    /// (bit-reversed) roots of unity they are taken over
+    pub fn to_evaluations<'a>(
+        &self,
+        kzg_settings: &'a KzgSettings,
+    ) -> Result<(Vec<Scalar>, &'a [Scalar]), KzgError> {
+        let domain = kzg_settings.roots_of_unity;
+        if domain.len() != NUM_FIELD_ELEMENTS_PER_BLOB {
+            return Err(KzgError::BadArgs(format!(
+                "The setup domain has {} points, but a blob has {} field elements",
+                domain.len(),
+                NUM_FIELD_ELEMENTS_PER_BLOB
+            )));
+        }
+
+        let evaluations =
+            self.as_polynomial_with_endianness(kzg_settings.field_element_endianness)?;
+        Ok((evaluations, domain))
+    }
+
+    /// Splits the blob into its raw field-element encodings, without checking they are canonical
+    pub fn to_field_element_bytes(&self) -> Vec<Bytes32> {
+        self.0
+            .chunks_exact(BYTES_PER_FIELD_ELEMENT)
+            .map(|slice| Bytes32::from_slice(slice).unwrap())
+            .collect()
+    }
+
+    /// Concatenates exactly [`NUM_FIELD_ELEMENTS_PER_BLOB`] raw field-element encodings into a blob
+    pub fn from_field_element_bytes(field_elements: &[Bytes32]) -> Result<Self, KzgError> {
+        if field_elements.len() != NUM_FIELD_ELEMENTS_PER_BLOB {
+            return Err(KzgError::InvalidBytesLength(format!(
+                "Expected {} field elements, got {}",
+                NUM_FIELD_ELEMENTS_PER_BLOB,
+                field_elements.len()
+            )));
+        }
+        let mut bytes = [0u8; BYTES_PER_BLOB];
+        for (chunk, field_element) in bytes
+            .chunks_exact_mut(BYTES_PER_FIELD_ELEMENT)
+            .zip(field_elements)
+        {
+            chunk.copy_from_slice(field_element.as_slice());
+        }
+        Ok(Blob(bytes))
+    }
+
+    /// Splits the blob into its first and last `NUM_FIELD_ELEMENTS_PER_BLOB / 2` field elements,
+    /// e.g. to store them on different shards. This is a storage convenience, not a
+    /// cryptographic split: neither half is a blob nor has a commitment of its own.
+    pub fn split_halves(&self) -> Result<(Vec<Scalar>, Vec<Scalar>), KzgError> {
+        let mut first = self.as_polynomial()?;
+        let second = first.split_off(NUM_FIELD_ELEMENTS_PER_BLOB / 2);
+        Ok((first, second))
+    }
+
+    /// Puts a blob back together from the halves of [`Self::split_halves`]
+    pub fn from_halves(a: &[Scalar], b: &[Scalar]) -> Result<Blob, KzgError> {
+        for half in [a, b] {
+            if half.len() != NUM_FIELD_ELEMENTS_PER_BLOB / 2 {
+                return Err(KzgError::MismatchedLengths {
+                    expected: NUM_FIELD_ELEMENTS_PER_BLOB / 2,
+                    got: half.len(),
+                });
+            }
+        }
+
+        let field_elements = a
+            .iter()
+            .chain(b)
+            .map(|scalar| scalar_to_blob_bytes(scalar, Endianness::Big))
+            .collect::<Vec<_>>();
+        Self::from_field_element_bytes(&field_elements)
+    }
+}
+
+/// A compressed KZG commitment
+#[derive(Debug, Clone)]
+pub struct KzgCommitment(Bytes48);
+
+impl KzgCommitment {
+    /// The commitment to the zero polynomial, i.e. the compressed point at infinity
+    pub fn identity() -> Self {
+        G1Affine::identity().into()
+    }
+
+    pub fn as_bytes(&self) -> &Bytes48 {
+        &self.0
+    }
+
+    /// Decompresses the commitment, checking it is a valid G1 point
+    pub fn to_g1(&self) -> Result<G1Affine, KzgError> {
+        safe_g1_affine_from_bytes(&self.0)
+    }
+
+    /// Negates the committed point, i.e. commits to the negated polynomial
+    pub fn try_neg(&self) -> Result<KzgCommitment, KzgError> {
+        self.to_g1().map(|point| (-point).into())
+    }
+
+    /// Adds the committed points, i.e. commits to the sum of the two polynomials
+    pub fn checked_add(&self, other: &Self) -> Result<KzgCommitment, KzgError> {
+        let sum = G1Projective::from(self.to_g1()?) + other.to_g1()?;
+        Ok(G1Affine::from(sum).into())
+    }
+
+    /// Compares the compressed encodings without decompressing either side.
+    ///
+    /// Every valid G1 point has exactly one compressed encoding, so for commitments that passed
+    /// [`Self::to_g1`] (or were built from a point) this is point equality.
+    pub fn eq_bytes(&self, other: &Self) -> bool {
+        self.0.as_slice() == other.0.as_slice()
+    }
+}
+
+/// Byte-wise, see [`KzgCommitment::eq_bytes`]
+impl PartialEq for KzgCommitment {
+    fn eq(&self, other: &Self) -> bool {
+        self.eq_bytes(other)
+    }
+}
+
+impl Eq for KzgCommitment {}
+
+impl From<Bytes48> for KzgCommitment {
+    fn from(value: Bytes48) -> Self {
+        KzgCommitment(value)
+    }
+}
+
+impl From<KzgCommitment> for Bytes48 {
+    fn from(value: KzgCommitment) -> Self {
+        value.0
+    }
+}
+
+impl TryFrom<&[u8]> for KzgCommitment {
+    type Error = KzgError;
+
+    /// Only checks the length, see [`KzgCommitment::to_g1`] for point validation
+    fn try_from(slice: &[u8]) -> Result<Self, Self::Error> {
+        Bytes48::from_slice(slice).map(Self)
+    }
+}
+
+impl From<G1Affine> for KzgCommitment {
+    fn from(value: G1Affine) -> Self {
+        KzgCommitment(value.to_compressed().into())
+    }
+}
+
+/// Adds up commitments, i.e. computes the commitment to the sum of the committed polynomials
+pub fn try_sum_commitments(commitments: &[KzgCommitment]) -> Result<KzgCommitment, KzgError> {
+    let sum = commitments
+        .iter()
+        .try_fold(G1Projective::identity(), |acc, commitment| {
+            commitment.to_g1().map(|point| acc + point)
+        })?;
+    Ok(G1Affine::from(sum).into())
+}
+
+/// # Panics
+///
+/// `Neg` cannot return an error, so this panics if the commitment is not a valid G1 point. Use
+/// [`KzgCommitment::try_neg`] for untrusted inputs.
+impl Neg for KzgCommitment {
+    type Output = KzgCommitment;
+
+    fn neg(self) -> Self::Output {
+        self.try_neg().expect("invalid commitment in negation")
+    }
+}
+
+/// # Panics
+///
+/// `Add` cannot return an error, so this panics if either commitment is not a valid G1 point.
+/// Use [`KzgCommitment::checked_add`] for untrusted inputs.
+impl Add for KzgCommitment {
+    type Output = KzgCommitment;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        self.checked_add(&rhs)
+            .expect("invalid commitment in addition")
+    }
+}
+
+/// # Panics
+///
+/// `Sum` cannot return an error, so this panics if any commitment is not a valid G1 point. Use
+/// [`try_sum_commitments`] for untrusted inputs.
+impl Sum for KzgCommitment {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        try_sum_commitments(&iter.collect::<Vec<_>>()).expect("invalid commitment in sum")
+    }
+}
+
+/// A polynomial in coefficient (monomial) form, of degree below [`NUM_FIELD_ELEMENTS_PER_BLOB`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Polynomial(Vec<Scalar>);
+
+impl Polynomial {
+    /// Coefficients, lowest degree first
+    pub fn coefficients(&self) -> &[Scalar] {
+        &self.0
+    }
+
+    /// Evaluates the polynomial at `x` with Horner's rule
+    pub fn evaluate(&self, x: &Scalar) -> Scalar {
+        self.0
+            .iter()
+            .rev()
+            .fold(Scalar::zero(), |acc, coefficient| acc * x + coefficient)
+    }
+
+    /// Evaluates the polynomial over the (bit-reversed) domain of the setup, with an FFT
+    pub fn to_evaluations(&self, kzg_settings: &KzgSettings) -> Result<Vec<Scalar>, KzgError> {
+        if self.0.len() > kzg_settings.field_elements_per_blob() {
+            return Err(KzgError::BadArgs(format!(
+                "A polynomial with {} coefficients does not fit a domain of {} points",
+                self.0.len(),
+                kzg_settings.field_elements_per_blob()
+            )));
+        }
+
+        Ok(coefficients_to_evaluations(
+            self.0.clone(),
+            kzg_settings.field_elements_per_blob(),
+        ))
+    }
+
+    /// Commits to the polynomial. The setup only ships Lagrange G1 points, so this goes through
+    /// the evaluation form, which gives the same commitment as the monomial points would.
+    pub fn commit(&self, kzg_settings: &KzgSettings) -> Result<KzgCommitment, KzgError> {
+        let evaluations = self.to_evaluations(kzg_settings)?;
+        poly_to_kzg_commitment(&evaluations, kzg_settings).map(Into::into)
+    }
+}
+
+impl TryFrom<&[Scalar]> for Polynomial {
+    type Error = KzgError;
+
+    fn try_from(coefficients: &[Scalar]) -> Result<Self, Self::Error> {
+        if coefficients.len() > NUM_FIELD_ELEMENTS_PER_BLOB {
+            return Err(KzgError::BadArgs(format!(
+                "Expected at most {} coefficients, got {}",
+                NUM_FIELD_ELEMENTS_PER_BLOB,
+                coefficients.len()
+            )));
+        }
+        Ok(Polynomial(coefficients.to_vec()))
+    }
 }
 
 #[cfg(test)]
@@ -63,4 +532,495 @@ mod tests {
         let bytes = crate::dtypes::Bytes48::from_slice(&[0u8; 48]).unwrap();
         assert_eq!(bytes.0.len(), 48);
     }
+
+    #[cfg(feature = "zeroize")]
+    #[test]
+    fn test_zeroize() {
+        use crate::dtypes::{Blob, Bytes32, Bytes48};
+        use zeroize::Zeroize;
+
+        fn wiped_on_drop<T: zeroize::ZeroizeOnDrop>() {}
+        wiped_on_drop::<Blob>();
+        wiped_on_drop::<Bytes32>();
+        wiped_on_drop::<Bytes48>();
+
+        let mut proof = Bytes48::from([0xab; 48]);
+        proof.zeroize();
+        assert_eq!(proof, Bytes48::from([0; 48]));
+    }
+
+    #[test]
+    fn test_hex_roundtrip() {
+        use crate::dtypes::{Bytes32, Bytes48};
+        use crate::enums::KzgError;
+        use bls12_381::G1Affine;
+
+        let hex_str = "0x637c904d316955b7282f980433d5cd9f40d0533c45d0a233c009bc7fe28b92e3";
+        let bytes = Bytes32::from_hex(hex_str).unwrap();
+        assert_eq!(bytes.to_hex(), hex_str);
+        assert_eq!(
+            Bytes32::from_hex(&hex_str[2..]).unwrap().as_slice(),
+            bytes.as_slice()
+        );
+        // Upper case is accepted, but always printed lower case
+        assert_eq!(
+            Bytes32::from_hex(&hex_str.to_uppercase()[2..])
+                .unwrap()
+                .to_hex(),
+            hex_str
+        );
+
+        let generator = Bytes48::from(G1Affine::generator().to_compressed());
+        assert_eq!(
+            Bytes48::from_hex(&generator.to_hex()).unwrap().as_slice(),
+            generator.as_slice()
+        );
+
+        assert!(matches!(
+            Bytes48::from_hex(hex_str),
+            Err(KzgError::InvalidBytesLength(message)) if message == "Expected 48 bytes, got 32"
+        ));
+        assert!(matches!(
+            Bytes32::from_hex("0x0g"),
+            Err(KzgError::InvalidHexFormat(_))
+        ));
+        assert!(matches!(
+            Bytes32::from_hex("0x123"),
+            Err(KzgError::InvalidHexFormat(_))
+        ));
+    }
+
+    #[test]
+    fn test_bytes_types_eq_and_hash() {
+        use crate::dtypes::{Blob, Bytes48};
+        use std::collections::{hash_map::DefaultHasher, HashSet};
+        use std::hash::{Hash, Hasher};
+
+        let proofs = [
+            Bytes48::from([1; 48]),
+            Bytes48::from([2; 48]),
+            Bytes48::from([1; 48]),
+        ];
+        let unique = proofs.iter().cloned().collect::<HashSet<_>>();
+        assert_eq!(unique.len(), 2);
+        assert!(unique.contains(&Bytes48::from([2; 48])));
+
+        let hash = |blob: &Blob| {
+            let mut hasher = DefaultHasher::new();
+            blob.hash(&mut hasher);
+            hasher.finish()
+        };
+        let mut last_byte = Blob::zero();
+        last_byte.0[crate::BYTES_PER_BLOB - 1] = 1;
+        assert_eq!(Blob::zero(), Blob::zero());
+        assert_eq!(hash(&Blob::zero()), hash(&Blob::zero()));
+        // The whole contents count, down to the last byte
+        assert_ne!(last_byte, Blob::zero());
+        assert_ne!(hash(&last_byte), hash(&Blob::zero()));
+    }
+
+    #[test]
+    fn test_try_from_slice() {
+        use crate::dtypes::{Bytes48, KzgCommitment};
+        use crate::enums::KzgError;
+
+        let bytes = [0xc0u8; 49];
+        let commitment: KzgCommitment = bytes[..48].try_into().unwrap();
+        assert_eq!(commitment, KzgCommitment::from(Bytes48::from([0xc0; 48])));
+        // Proofs are plain `Bytes48`
+        let proof: Bytes48 = bytes[..48].try_into().unwrap();
+        assert_eq!(proof.as_slice(), &bytes[..48]);
+
+        for len in [0, 47, 49] {
+            assert!(matches!(
+                KzgCommitment::try_from(&bytes[..len]),
+                Err(KzgError::InvalidBytesLength(_))
+            ));
+            assert!(matches!(
+                Bytes48::try_from(&bytes[..len]),
+                Err(KzgError::InvalidBytesLength(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_sum_commitments() {
+        use crate::dtypes::{try_sum_commitments, Bytes48, KzgCommitment};
+        use bls12_381::{G1Affine, G1Projective, Scalar};
+
+        let points =
+            [3u64, 5, 11].map(|k| G1Affine::from(G1Projective::generator() * Scalar::from(k)));
+        let commitments = points.map(KzgCommitment::from);
+
+        let expected = G1Affine::from(G1Projective::generator() * Scalar::from(19));
+        let manual = G1Affine::from(points[0] + G1Projective::from(points[1]) + points[2]);
+        assert_eq!(manual, expected);
+
+        let sum: KzgCommitment = commitments.clone().into_iter().sum();
+        assert_eq!(sum.to_g1().unwrap(), expected);
+        assert_eq!(
+            try_sum_commitments(&commitments).unwrap().to_g1().unwrap(),
+            expected
+        );
+
+        // The empty sum is the point at infinity
+        assert!(bool::from(
+            try_sum_commitments(&[])
+                .unwrap()
+                .to_g1()
+                .unwrap()
+                .is_identity()
+        ));
+
+        // Invalid commitments are reported instead of panicking
+        let invalid = KzgCommitment::from(Bytes48::from_slice(&[0xff; 48]).unwrap());
+        assert!(try_sum_commitments(&[commitments[0].clone(), invalid]).is_err());
+    }
+
+    #[test]
+    fn test_add_commitments() {
+        use crate::dtypes::{Bytes48, KzgCommitment, Polynomial};
+        use crate::KzgSettings;
+        use bls12_381::Scalar;
+
+        let kzg_settings = KzgSettings::load_trusted_setup_file().unwrap();
+        let a = [3u64, 2, 1].map(Scalar::from);
+        let b = [7u64, 0, 5, 9].map(Scalar::from);
+        let mut sum = b;
+        for (s, a) in sum.iter_mut().zip(a) {
+            *s += a;
+        }
+        let commit = |coefficients: &[Scalar]| {
+            Polynomial::try_from(coefficients)
+                .unwrap()
+                .commit(&kzg_settings)
+                .unwrap()
+        };
+
+        let expected = commit(&sum);
+        assert_eq!(commit(&a) + commit(&b), expected);
+        assert_eq!(commit(&a).checked_add(&commit(&b)).unwrap(), expected);
+        assert_eq!(commit(&a) + KzgCommitment::identity(), commit(&a));
+
+        let invalid = KzgCommitment::from(Bytes48::from([0xff; 48]));
+        assert!(commit(&a).checked_add(&invalid).is_err());
+        assert!(invalid.checked_add(&commit(&a)).is_err());
+    }
+
+    #[test]
+    fn test_blob_split_halves_roundtrip() {
+        use crate::dtypes::Blob;
+        use crate::{KzgError, NUM_FIELD_ELEMENTS_PER_BLOB};
+        use bls12_381::Scalar;
+
+        let a = (0..NUM_FIELD_ELEMENTS_PER_BLOB as u64 / 2)
+            .map(Scalar::from)
+            .collect::<Vec<_>>();
+        let b = a.iter().map(|scalar| -scalar).collect::<Vec<_>>();
+        let blob = Blob::from_halves(&a, &b).unwrap();
+        assert_eq!(blob.split_halves().unwrap(), (a.clone(), b.clone()));
+
+        let (first, second) = blob.split_halves().unwrap();
+        assert_eq!(Blob::from_halves(&first, &second).unwrap(), blob);
+
+        assert!(matches!(
+            Blob::from_halves(&a[1..], &b),
+            Err(KzgError::MismatchedLengths { got, .. }) if got == a.len() - 1
+        ));
+        assert!(Blob::from_halves(&a, &[b.clone(), b].concat()).is_err());
+        assert!(Blob::from_slice(&[0xff; crate::BYTES_PER_BLOB])
+            .unwrap()
+            .split_halves()
+            .is_err());
+    }
+
+    #[test]
+    fn test_blob_field_element_bytes_roundtrip() {
+        use crate::dtypes::{Blob, Bytes32};
+        use crate::NUM_FIELD_ELEMENTS_PER_BLOB;
+
+        // Not canonical field elements: the raw conversions must not care
+        let bytes = (0..crate::BYTES_PER_BLOB)
+            .map(|i| (i % 251) as u8)
+            .collect::<Vec<_>>();
+        let blob = Blob::from_slice(&bytes).unwrap();
+
+        let field_elements = blob.to_field_element_bytes();
+        assert_eq!(field_elements.len(), NUM_FIELD_ELEMENTS_PER_BLOB);
+        assert_eq!(field_elements[1].as_slice(), &bytes[32..64]);
+
+        let roundtrip = Blob::from_field_element_bytes(&field_elements).unwrap();
+        assert_eq!(roundtrip.as_slice(), blob.as_slice());
+
+        assert!(Blob::from_field_element_bytes(&field_elements[1..]).is_err());
+        assert!(Blob::from_field_element_bytes(&[Bytes32::from([0u8; 32])]).is_err());
+    }
+
+    #[test]
+    fn test_blob_from_mmap() {
+        use crate::dtypes::Blob;
+        use crate::BYTES_PER_BLOB;
+
+        // Two blobs after a 7-byte header
+        let mut file = vec![0xaa; 7];
+        file.extend((0..2 * BYTES_PER_BLOB).map(|i| (i / BYTES_PER_BLOB) as u8 + 1));
+
+        let second = Blob::from_mmap(&file, 7 + BYTES_PER_BLOB).unwrap();
+        assert!(second.as_slice().iter().all(|byte| *byte == 2));
+        assert_eq!(
+            Blob::from_mmap(&file, 7).unwrap().as_slice(),
+            &file[7..7 + BYTES_PER_BLOB]
+        );
+
+        assert!(Blob::from_mmap(&file, 8 + BYTES_PER_BLOB).is_err());
+        assert!(Blob::from_mmap(&file, usize::MAX).is_err());
+    }
+
+    #[test]
+    fn test_blob_from_hex_and_padded_bytes() {
+        use crate::dtypes::Blob;
+        use crate::enums::KzgError;
+        use crate::BYTES_PER_BLOB;
+
+        let data = b"application data";
+        let blob = Blob::from_bytes_padded(data).unwrap();
+        assert_eq!(&blob.as_slice()[..data.len()], data);
+        assert!(blob.as_slice()[data.len()..].iter().all(|byte| *byte == 0));
+        assert_eq!(
+            Blob::from_bytes_padded(&[]).unwrap().as_slice(),
+            Blob::zero().as_slice()
+        );
+
+        let full = vec![7u8; BYTES_PER_BLOB];
+        assert_eq!(
+            Blob::from_bytes_padded(&full).unwrap().as_slice(),
+            full.as_slice()
+        );
+        assert!(matches!(
+            Blob::from_bytes_padded(&[0; BYTES_PER_BLOB + 1]),
+            Err(KzgError::InvalidBytesLength(_))
+        ));
+
+        let hex_str = blob.to_hex();
+        assert_eq!(hex_str.len(), 2 + 2 * BYTES_PER_BLOB);
+        assert_eq!(
+            Blob::from_hex(&hex_str).unwrap().as_slice(),
+            blob.as_slice()
+        );
+        assert!(matches!(
+            Blob::from_hex(&hex_str[..hex_str.len() - 2]),
+            Err(KzgError::InvalidBytesLength(_))
+        ));
+    }
+
+    #[test]
+    fn test_blob_debug_is_redacted() {
+        use crate::dtypes::Blob;
+
+        #[derive(Debug)]
+        #[allow(dead_code)]
+        struct Sidecar {
+            index: u64,
+            blob: Blob,
+        }
+
+        let blob = Blob::from_slice(&[0xab; crate::BYTES_PER_BLOB]).unwrap();
+        let debug = format!("{blob:?}");
+        assert!(debug.starts_with("Blob { len: 131072, sha256: 0x"));
+        assert!(debug.len() < 64);
+        assert!(!debug.contains("171, 171"));
+        assert_ne!(
+            debug,
+            format!(
+                "{:?}",
+                Blob::from_slice(&[0; crate::BYTES_PER_BLOB]).unwrap()
+            )
+        );
+
+        let sidecar = format!("{:?}", Sidecar { index: 0, blob });
+        assert!(sidecar.contains(&debug));
+        assert!(sidecar.len() < 128);
+    }
+
+    #[test]
+    fn test_blob_to_evaluations() {
+        use crate::dtypes::{Blob, Endianness};
+        use crate::kzg_proof::scalar_to_blob_bytes;
+        use crate::KzgSettings;
+        use bls12_381::Scalar;
+
+        let kzg_settings = KzgSettings::load_trusted_setup_file().unwrap();
+        let field_elements = (0..crate::NUM_FIELD_ELEMENTS_PER_BLOB as u64)
+            .map(|i| scalar_to_blob_bytes(&Scalar::from(i), Endianness::Big))
+            .collect::<Vec<_>>();
+        let blob = Blob::from_field_element_bytes(&field_elements).unwrap();
+
+        let (evaluations, domain) = blob.to_evaluations(&kzg_settings).unwrap();
+        assert_eq!(evaluations.len(), domain.len());
+        assert_eq!(evaluations, blob.as_polynomial().unwrap());
+        assert_eq!(domain, kzg_settings.roots_of_unity);
+
+        // Non-canonical field elements are still rejected
+        assert!(Blob::from_slice(&[0xff; crate::BYTES_PER_BLOB])
+            .unwrap()
+            .to_evaluations(&kzg_settings)
+            .is_err());
+    }
+
+    #[test]
+    fn test_blob_rejects_non_canonical_field_elements() {
+        use crate::dtypes::{Blob, Bytes32};
+        use crate::{enums::KzgError, MODULUS};
+        use bls12_381::Scalar;
+
+        // MODULUS is stored least significant limb first, blobs are big-endian
+        let mut modulus = [0u8; 32];
+        for (chunk, limb) in modulus.chunks_exact_mut(8).zip(MODULUS.iter().rev()) {
+            chunk.copy_from_slice(&limb.to_be_bytes());
+        }
+        let mut modulus_minus_one = modulus;
+        modulus_minus_one[31] -= 1;
+
+        let mut field_elements = Blob::zero().to_field_element_bytes();
+        field_elements[7] = Bytes32::from(modulus_minus_one);
+        let polynomial = Blob::from_field_element_bytes(&field_elements)
+            .unwrap()
+            .as_polynomial()
+            .unwrap();
+        assert_eq!(polynomial[7], -Scalar::one());
+
+        // The modulus itself would silently reduce to zero
+        field_elements[7] = Bytes32::from(modulus);
+        assert!(matches!(
+            Blob::from_field_element_bytes(&field_elements)
+                .unwrap()
+                .as_polynomial(),
+            Err(KzgError::BadFieldElement(_))
+        ));
+    }
+
+    #[test]
+    fn test_commitment_eq_bytes() {
+        use crate::dtypes::{Bytes48, KzgCommitment};
+        use bls12_381::{G1Affine, G1Projective, Scalar};
+
+        let point = G1Projective::generator() * Scalar::from(1234);
+        // The same point reached through different representations
+        let a = KzgCommitment::from(G1Affine::from(point));
+        let b = KzgCommitment::from(G1Affine::from(
+            G1Projective::generator() * Scalar::from(1000)
+                + G1Projective::generator() * Scalar::from(234),
+        ));
+        let c = KzgCommitment::from(b.to_g1().unwrap());
+
+        assert_eq!(a.as_bytes().as_slice(), b.as_bytes().as_slice());
+        assert!(a.eq_bytes(&b));
+        assert_eq!(a, b);
+        assert_eq!(b, c);
+
+        let other = KzgCommitment::from(G1Affine::generator());
+        assert!(!a.eq_bytes(&other));
+        assert_ne!(a, other);
+        assert_ne!(a, KzgCommitment::from(Bytes48::from([0u8; 48])));
+    }
+
+    #[test]
+    fn test_neg_commitment() {
+        use crate::dtypes::{try_sum_commitments, Bytes48, KzgCommitment};
+        use bls12_381::{G1Affine, G1Projective, Scalar};
+
+        let commitment =
+            KzgCommitment::from(G1Affine::from(G1Projective::generator() * Scalar::from(42)));
+        let negated = -commitment.clone();
+        assert_ne!(negated, commitment);
+        assert_eq!(-negated.clone(), commitment);
+
+        let sum = try_sum_commitments(&[commitment, negated]).unwrap();
+        assert_eq!(sum, KzgCommitment::from(G1Affine::identity()));
+
+        // The point at infinity is its own negation
+        let identity = KzgCommitment::from(G1Affine::identity());
+        assert_eq!(-identity.clone(), identity);
+
+        assert!(KzgCommitment::from(Bytes48::from([0xff; 48]))
+            .try_neg()
+            .is_err());
+    }
+
+    #[test]
+    fn test_polynomial_commit_and_open() {
+        use crate::dtypes::{KzgCommitment, Polynomial};
+        use crate::kzg_proof::{compute_kzg_proof_impl, verify_kzg_proof_impl};
+        use crate::KzgSettings;
+        use bls12_381::{G1Affine, G1Projective, Scalar};
+
+        let kzg_settings = KzgSettings::load_trusted_setup_file().unwrap();
+
+        // p(X) = 3 + 2X + X^2
+        let coefficients = [3, 2, 1].map(Scalar::from);
+        let polynomial = Polynomial::try_from(&coefficients[..]).unwrap();
+        let commitment = polynomial.commit(&kzg_settings).unwrap();
+
+        // The constant polynomial c commits to [c]G1, as a monomial commitment would
+        let constant = Polynomial::try_from(&[Scalar::from(5)][..]).unwrap();
+        assert_eq!(
+            constant.commit(&kzg_settings).unwrap(),
+            KzgCommitment::from(G1Affine::from(G1Projective::generator() * Scalar::from(5)))
+        );
+
+        let z = Scalar::from(10);
+        let evaluations = polynomial.to_evaluations(&kzg_settings).unwrap();
+        let (proof, y) = compute_kzg_proof_impl(&evaluations, z, &kzg_settings).unwrap();
+        assert_eq!(y, polynomial.evaluate(&z));
+        assert_eq!(y, Scalar::from(123));
+        assert!(
+            verify_kzg_proof_impl(commitment.to_g1().unwrap(), z, y, proof, &kzg_settings).unwrap()
+        );
+
+        let too_long = vec![Scalar::one(); crate::NUM_FIELD_ELEMENTS_PER_BLOB + 1];
+        assert!(Polynomial::try_from(&too_long[..]).is_err());
+    }
+
+    #[test]
+    fn test_polynomial_to_evaluations_matches_horner() {
+        use crate::dtypes::Polynomial;
+        use crate::trusted_setup::generate_insecure_setup;
+        use bls12_381::Scalar;
+
+        let kzg_settings = generate_insecure_setup(Scalar::from(7), 16);
+        // Full degree, and shorter than the domain
+        for len in [16, 5, 0] {
+            let coefficients = (0..len as u64)
+                .map(|i| Scalar::from(i * i + 3))
+                .collect::<Vec<_>>();
+            let polynomial = Polynomial::try_from(&coefficients[..]).unwrap();
+            let expected = kzg_settings
+                .roots_of_unity
+                .iter()
+                .map(|root| polynomial.evaluate(root))
+                .collect::<Vec<_>>();
+            assert_eq!(polynomial.to_evaluations(&kzg_settings).unwrap(), expected);
+        }
+
+        let too_long = Polynomial::try_from(&[Scalar::one(); 17][..]).unwrap();
+        assert!(too_long.to_evaluations(&kzg_settings).is_err());
+    }
+
+    #[test]
+    fn test_zero_blob_commits_to_identity() {
+        use crate::dtypes::{Blob, KzgCommitment};
+        use crate::kzg_proof::blob_to_kzg_commitment;
+        use crate::KzgSettings;
+
+        let kzg_settings = KzgSettings::load_trusted_setup_file().unwrap();
+        let commitment =
+            KzgCommitment::from(blob_to_kzg_commitment(&Blob::zero(), &kzg_settings).unwrap());
+
+        assert_eq!(commitment, KzgCommitment::identity());
+        // Compression flag and infinity flag set, everything else zero
+        assert_eq!(KzgCommitment::identity().as_bytes().as_slice()[0], 0xc0);
+        assert!(KzgCommitment::identity().as_bytes().as_slice()[1..]
+            .iter()
+            .all(|byte| *byte == 0));
+    }
 }
@@ -15,6 +15,46 @@ pub enum KzgError {
     InvalidHexFormat(String),
     /// The provided trusted setup params are invalid.
     InvalidTrustedSetup(String),
+    /// A field element is not canonical, i.e. not strictly below the scalar field modulus.
+    BadFieldElement(String),
+    /// The bytes do not decompress to a point on the curve.
+    NotOnCurve(String),
+    /// The point is on the curve but outside the prime-order subgroup.
+    NotInSubgroup(String),
+    /// Inputs that must come in matching numbers, e.g. blobs and their commitments, do not.
+    MismatchedLengths { expected: usize, got: usize },
+    /// A strict entry point got no inputs where at least one is required.
+    Empty,
+    /// A fast path needs values the settings were built without, see
+    /// `KzgSettingsBuilder::precompute`.
+    SetupNotPrecomputed,
+    /// A cell index appears more than once, e.g. among the cells to recover a blob from.
+    DuplicateCellIndex { index: u64 },
+    /// A cell index is not below `CELLS_PER_EXT_BLOB`.
+    CellIndexOutOfRange { index: u64 },
+}
+
+impl KzgError {
+    /// Prefixes the message with `context`, e.g. the input it is about, keeping the variant
+    #[allow(dead_code)] // build.rs includes this file without calling it
+    pub(crate) fn with_context(self, context: impl fmt::Display) -> Self {
+        let prefix = |s: String| format!("{}: {}", context, s);
+        match self {
+            Self::BadArgs(s) => Self::BadArgs(prefix(s)),
+            Self::InvalidBytesLength(s) => Self::InvalidBytesLength(prefix(s)),
+            Self::InvalidHexFormat(s) => Self::InvalidHexFormat(prefix(s)),
+            Self::InvalidTrustedSetup(s) => Self::InvalidTrustedSetup(prefix(s)),
+            Self::BadFieldElement(s) => Self::BadFieldElement(prefix(s)),
+            Self::NotOnCurve(s) => Self::NotOnCurve(prefix(s)),
+            Self::NotInSubgroup(s) => Self::NotInSubgroup(prefix(s)),
+            Self::InternalError
+            | Self::MismatchedLengths { .. }
+            | Self::Empty
+            | Self::SetupNotPrecomputed
+            | Self::DuplicateCellIndex { .. }
+            | Self::CellIndexOutOfRange { .. } => self,
+        }
+    }
 }
 
 impl fmt::Display for KzgError {
@@ -23,8 +63,73 @@ impl fmt::Display for KzgError {
             Self::BadArgs(s)
             | Self::InvalidBytesLength(s)
             | Self::InvalidHexFormat(s)
-            | Self::InvalidTrustedSetup(s) => f.write_str(s),
+            | Self::InvalidTrustedSetup(s)
+            | Self::BadFieldElement(s)
+            | Self::NotOnCurve(s)
+            | Self::NotInSubgroup(s) => f.write_str(s),
             Self::InternalError => f.write_str("Internal error"),
+            Self::Empty => f.write_str("Expected at least one element"),
+            Self::SetupNotPrecomputed => {
+                f.write_str("The setup was built without the precomputed values this call needs")
+            }
+            Self::MismatchedLengths { expected, got } => {
+                write!(f, "Expected {} elements, got {}", expected, got)
+            }
+            Self::DuplicateCellIndex { index } => write!(f, "Cell index {} is duplicated", index),
+            Self::CellIndexOutOfRange { index } => {
+                write!(f, "Cell index {} is out of range", index)
+            }
         }
     }
 }
+
+/// Lets the errors flow into `Box<dyn Error>`, `anyhow` and the like
+#[cfg(feature = "std")]
+impl std::error::Error for KzgError {}
+
+/// Lets `?` mix [`KzgError`] with IO errors, e.g. in setup file loaders
+#[cfg(feature = "std")]
+impl From<KzgError> for std::io::Error {
+    fn from(error: KzgError) -> Self {
+        use alloc::string::ToString;
+        std::io::Error::new(std::io::ErrorKind::InvalidData, error.to_string())
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+
+    #[test]
+    fn test_into_io_error() {
+        fn load() -> std::io::Result<()> {
+            Err(KzgError::InvalidBytesLength(
+                "Invalid slice length".to_string(),
+            ))?;
+            Ok(())
+        }
+
+        let error = load().unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+        assert_eq!(error.to_string(), "Invalid slice length");
+    }
+
+    #[test]
+    fn test_boxed_error() {
+        fn parse() -> Result<(), alloc::boxed::Box<dyn std::error::Error>> {
+            Err(KzgError::BadFieldElement(
+                "Field element is not canonical".to_string(),
+            ))?;
+            Ok(())
+        }
+
+        let error = parse().unwrap_err();
+        assert!(error.source().is_none());
+        assert_eq!(error.to_string(), "Field element is not canonical");
+        assert!(matches!(
+            error.downcast_ref::<KzgError>(),
+            Some(KzgError::BadFieldElement(_))
+        ));
+    }
+}
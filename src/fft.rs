@@ -0,0 +1,261 @@
+//! Radix-2 FFTs over the roots of unity of the scalar field, and the bit-reversed order of the
+//! EIP-4844 domain: the setup, the roots of unity and the blobs themselves list the evaluation
+//! points in it.
+
+use crate::{
+    enums::KzgError, kzg_proof::compute_powers, trusted_setup::domain_generator,
+    NUM_FIELD_ELEMENTS_PER_BLOB,
+};
+
+use alloc::{string::ToString, vec::Vec};
+use bls12_381::Scalar;
+use core::ops::{Add, Mul, Sub};
+use spin::Once;
+
+/// Expands a primitive `width`-th root of unity into the domain `1, root, ..., root^(width - 1)`,
+/// e.g. the 4096 points of the blob domain from the matching [`crate::SCALE2_ROOT_OF_UNITY`]
+/// entry. Errors unless `width` is a power of two and `root` has order exactly `width`.
+pub fn expand_root_of_unity(root: &Scalar, width: usize) -> Result<Vec<Scalar>, KzgError> {
+    if !width.is_power_of_two() {
+        return Err(KzgError::BadArgs(format!(
+            "The domain size must be a power of two, got {}",
+            width
+        )));
+    }
+
+    let roots = compute_powers(root, width);
+    // For a power of two, order exactly `width` means root^width = 1 and root^(width / 2) = -1
+    let closes = roots[width - 1] * root == Scalar::one();
+    let primitive = width == 1 || roots[width / 2] == -Scalar::one();
+    if !(closes && primitive) {
+        return Err(KzgError::BadArgs(format!(
+            "The root is not a primitive {}-th root of unity",
+            width
+        )));
+    }
+    Ok(roots)
+}
+
+/// Evaluates the polynomial with coefficients `values` over the domain `roots`, as returned by
+/// [`expand_root_of_unity`], in place and in natural order. Works over G1 as well, where the
+/// coefficients are points.
+pub fn fft<T>(values: &mut [T], roots: &[Scalar]) -> Result<(), KzgError>
+where
+    T: Copy + Add<Output = T> + Sub<Output = T> + Mul<Scalar, Output = T>,
+{
+    check_domain(values.len(), roots)?;
+    fft_unchecked(values, roots);
+    Ok(())
+}
+
+/// Inverse of [`fft`]: interpolates the coefficients from the evaluations over `roots`
+pub fn ifft<T>(values: &mut [T], roots: &[Scalar]) -> Result<(), KzgError>
+where
+    T: Copy + Add<Output = T> + Sub<Output = T> + Mul<Scalar, Output = T>,
+{
+    check_domain(values.len(), roots)?;
+    ifft_unchecked(values, roots);
+    Ok(())
+}
+
+fn check_domain(len: usize, roots: &[Scalar]) -> Result<(), KzgError> {
+    if len != roots.len() {
+        return Err(KzgError::MismatchedLengths {
+            expected: roots.len(),
+            got: len,
+        });
+    }
+    if !len.is_power_of_two() {
+        return Err(KzgError::BadArgs(
+            "The domain size must be a power of two".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// The domain of `size` points, a power of two, in natural order
+pub(crate) fn domain(size: usize) -> Vec<Scalar> {
+    compute_powers(&domain_generator(size.trailing_zeros() as usize), size)
+}
+
+/// Evaluates the polynomial with `coefficients`, at most `size` of them, over the domain of
+/// `size` points, returning the evaluations in the bit-reversed order blobs list them in
+pub(crate) fn coefficients_to_evaluations(
+    mut coefficients: Vec<Scalar>,
+    size: usize,
+) -> Vec<Scalar> {
+    coefficients.resize(size, Scalar::zero());
+    fft_unchecked(&mut coefficients, &domain(size));
+    bit_reversal_permutation(&mut coefficients);
+    coefficients
+}
+
+/// [`fft`] for callers that already know the sizes match
+pub(crate) fn fft_unchecked<T>(values: &mut [T], roots: &[Scalar])
+where
+    T: Copy + Add<Output = T> + Sub<Output = T> + Mul<Scalar, Output = T>,
+{
+    let n = values.len();
+    bit_reversal_permutation(values);
+
+    let mut half = 1;
+    while half < n {
+        // The roots of order 2 * half are every `stride`-th root of the domain
+        let stride = n / (2 * half);
+        for chunk in values.chunks_exact_mut(2 * half) {
+            let (low, high) = chunk.split_at_mut(half);
+            for (j, (a, b)) in low.iter_mut().zip(high).enumerate() {
+                // Skip the multiplication by one, which is a full scalar mul over G1
+                let t = if j == 0 { *b } else { *b * roots[j * stride] };
+                *b = *a - t;
+                *a = *a + t;
+            }
+        }
+        half *= 2;
+    }
+}
+
+/// [`ifft`] for callers that already know the sizes match
+pub(crate) fn ifft_unchecked<T>(values: &mut [T], roots: &[Scalar])
+where
+    T: Copy + Add<Output = T> + Sub<Output = T> + Mul<Scalar, Output = T>,
+{
+    // The inverse roots are the same powers read backwards
+    let n = roots.len();
+    let inverse_roots = (0..n).map(|i| roots[(n - i) % n]).collect::<Vec<_>>();
+    fft_unchecked(values, &inverse_roots);
+
+    let n_inv = Scalar::from(n as u64).invert().unwrap();
+    for value in values.iter_mut() {
+        *value = *value * n_inv;
+    }
+}
+
+/// Position of `index` once a slice of `len` elements, a power of two, is bit-reversed
+pub fn bit_reversed_index(index: usize, len: usize) -> usize {
+    index
+        .reverse_bits()
+        .checked_shr(usize::BITS - len.trailing_zeros())
+        .unwrap_or(0)
+}
+
+/// The bit-reversal permutation of the blob domain, computed on first use and then shared
+pub fn blob_bit_reversal_indices() -> &'static [usize] {
+    static INDICES: Once<Vec<usize>> = Once::new();
+    INDICES.call_once(|| {
+        (0..NUM_FIELD_ELEMENTS_PER_BLOB)
+            .map(|i| bit_reversed_index(i, NUM_FIELD_ELEMENTS_PER_BLOB))
+            .collect()
+    })
+}
+
+/// Permutes a slice whose length is a power of two into bit-reversed order, its own inverse.
+/// Blob-sized slices go through the cached [`blob_bit_reversal_indices`].
+pub fn bit_reversal_permutation<T>(values: &mut [T]) {
+    if values.len() == NUM_FIELD_ELEMENTS_PER_BLOB {
+        for (i, &j) in blob_bit_reversal_indices().iter().enumerate() {
+            if i < j {
+                values.swap(i, j);
+            }
+        }
+        return;
+    }
+
+    for i in 0..values.len() {
+        let j = bit_reversed_index(i, values.len());
+        if i < j {
+            values.swap(i, j);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{dtypes::Polynomial, trusted_setup::get_roots_of_unity};
+
+    #[test]
+    fn test_bit_reversal_permutation_is_an_involution() {
+        for len in [
+            1,
+            2,
+            16,
+            NUM_FIELD_ELEMENTS_PER_BLOB,
+            2 * NUM_FIELD_ELEMENTS_PER_BLOB,
+        ] {
+            let natural = (0..len).collect::<Vec<_>>();
+            let mut values = natural.clone();
+
+            bit_reversal_permutation(&mut values);
+            if len > 2 {
+                assert_ne!(values, natural);
+            }
+            bit_reversal_permutation(&mut values);
+            assert_eq!(values, natural);
+        }
+
+        let mut values = (0..8).collect::<Vec<_>>();
+        bit_reversal_permutation(&mut values);
+        assert_eq!(values, [0, 4, 2, 6, 1, 5, 3, 7]);
+    }
+
+    #[test]
+    fn test_blob_bit_reversal_indices() {
+        let indices = blob_bit_reversal_indices();
+        assert_eq!(indices.len(), NUM_FIELD_ELEMENTS_PER_BLOB);
+        assert!(core::ptr::eq(indices, blob_bit_reversal_indices()));
+
+        // The setup lists the roots of unity in this order
+        let generator = domain_generator(NUM_FIELD_ELEMENTS_PER_BLOB.trailing_zeros() as usize);
+        let mut roots = get_roots_of_unity().to_vec();
+        bit_reversal_permutation(&mut roots);
+        let mut power = Scalar::one();
+        for root in roots {
+            assert_eq!(root, power);
+            power *= generator;
+        }
+    }
+
+    #[test]
+    fn test_expand_root_of_unity() {
+        let generator = domain_generator(NUM_FIELD_ELEMENTS_PER_BLOB.trailing_zeros() as usize);
+        let roots = expand_root_of_unity(&generator, NUM_FIELD_ELEMENTS_PER_BLOB).unwrap();
+        assert_eq!(roots, domain(NUM_FIELD_ELEMENTS_PER_BLOB));
+
+        // The setup holds the same domain, bit-reversed
+        let mut setup_roots = get_roots_of_unity().to_vec();
+        bit_reversal_permutation(&mut setup_roots);
+        assert_eq!(roots, setup_roots);
+
+        assert!(expand_root_of_unity(&generator, 3).is_err());
+        // Order 2048, not 4096
+        assert!(expand_root_of_unity(&generator.square(), NUM_FIELD_ELEMENTS_PER_BLOB).is_err());
+        // Not a root of unity of order 2048 at all
+        assert!(expand_root_of_unity(&generator, NUM_FIELD_ELEMENTS_PER_BLOB / 2).is_err());
+    }
+
+    #[test]
+    fn test_fft_roundtrip() {
+        let coefficients = (0..16u64).map(Scalar::from).collect::<Vec<_>>();
+        let roots = expand_root_of_unity(&domain_generator(4), 16).unwrap();
+
+        let mut values = coefficients.clone();
+        fft(&mut values, &roots).unwrap();
+        let polynomial = Polynomial::try_from(coefficients.as_slice()).unwrap();
+        for (value, root) in values.iter().zip(&roots) {
+            assert_eq!(*value, polynomial.evaluate(root));
+        }
+
+        ifft(&mut values, &roots).unwrap();
+        assert_eq!(values, coefficients);
+
+        assert!(matches!(
+            fft(&mut values[..8], &roots),
+            Err(KzgError::MismatchedLengths {
+                expected: 16,
+                got: 8
+            })
+        ));
+        assert!(ifft(&mut values[..3], &roots[..3]).is_err());
+    }
+}
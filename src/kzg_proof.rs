@@ -2,73 +2,217 @@ use core::num::NonZeroUsize;
 use core::ops::Mul;
 
 use crate::enums::KzgError;
-use crate::trusted_setup::KzgSettings;
+use crate::fft::{bit_reversal_permutation, fft, ifft};
+use crate::msm::g1_lincomb_with_window;
+use crate::trusted_setup::{prepared_g2_generator, KzgSettings, DEFAULT_FIAT_SHAMIR_DOMAIN};
 use crate::{
-    dtypes::*, pairings_verify, BYTES_PER_BLOB, BYTES_PER_COMMITMENT, BYTES_PER_FIELD_ELEMENT,
-    BYTES_PER_PROOF, CHALLENGE_INPUT_SIZE, DOMAIN_STR_LENGTH, FIAT_SHAMIR_PROTOCOL_DOMAIN, MODULUS,
-    NUM_FIELD_ELEMENTS_PER_BLOB, RANDOM_CHALLENGE_KZG_BATCH_DOMAIN,
+    dtypes::*, pairings_verify, PairingCheck, BYTES_PER_BLOB, BYTES_PER_COMMITMENT,
+    BYTES_PER_FIELD_ELEMENT, BYTES_PER_PROOF, DOMAIN_STR_LENGTH, MODULUS,
+    NUM_FIELD_ELEMENTS_PER_BLOB, VERSIONED_HASH_VERSION_KZG,
 };
 
-use alloc::{string::ToString, vec::Vec};
+use alloc::{borrow::Cow, collections::BTreeMap, string::ToString, vec::Vec};
 pub use bls12_381::{G1Affine, G1Projective, G2Affine, G2Projective, Scalar};
-use ff::derive::sbb;
+use bls12_381::{G2Prepared, MillerLoopResult};
+use ff::{derive::sbb, PrimeField};
+#[cfg(feature = "futures")]
+use futures::{Stream, StreamExt};
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
 pub use sha2::{Digest, Sha256};
+pub use subtle::Choice;
 
+/// Decompresses a commitment or proof, rejecting points off the curve with
+/// [`KzgError::NotOnCurve`] and points outside the prime-order subgroup with
+/// [`KzgError::NotInSubgroup`]. The point at infinity is accepted.
 pub fn safe_g1_affine_from_bytes(bytes: &Bytes48) -> Result<G1Affine, KzgError> {
-    let g1 = G1Affine::from_compressed(&(bytes.clone().into()));
-    if g1.is_none().into() {
-        return Err(KzgError::BadArgs(
-            "Failed to parse G1Affine from bytes".to_string(),
+    let point = g1_affine_from_bytes_unchecked(bytes)?;
+    if !bool::from(point.is_torsion_free()) {
+        return Err(KzgError::NotInSubgroup(
+            "The G1 point is not in the prime-order subgroup".to_string(),
         ));
     }
-    Ok(g1.unwrap())
+    Ok(point)
 }
 
-pub fn safe_scalar_affine_from_bytes(bytes: &Bytes32) -> Result<Scalar, KzgError> {
-    let lendian: [u8; 32] = Into::<[u8; 32]>::into(bytes.clone())
-        .iter()
-        .rev()
-        .copied()
-        .collect::<Vec<u8>>()
-        .try_into()
-        .unwrap();
+/// The `validate_kzg_g1` of the EIP-4844 spec, i.e. [`safe_g1_affine_from_bytes`]: small-order
+/// points would otherwise let a forged commitment or proof pass verification.
+pub fn validate_kzg_g1(bytes: &Bytes48) -> Result<G1Affine, KzgError> {
+    safe_g1_affine_from_bytes(bytes)
+}
+
+/// Parses a compressed G1 point according to `policy`. With [`SubgroupPolicy::Reject`] this is
+/// [`safe_g1_affine_from_bytes`], with [`SubgroupPolicy::ClearCofactor`] any point on the curve
+/// is accepted and the ones outside the subgroup are multiplied by the cofactor.
+pub fn g1_affine_from_bytes_with_policy(
+    bytes: &Bytes48,
+    policy: SubgroupPolicy,
+) -> Result<G1Affine, KzgError> {
+    match policy {
+        SubgroupPolicy::Reject => safe_g1_affine_from_bytes(bytes),
+        SubgroupPolicy::ClearCofactor => {
+            let point = g1_affine_from_bytes_unchecked(bytes)?;
+            // Clearing also scales subgroup points, which must stay untouched to verify
+            if bool::from(point.is_torsion_free()) {
+                return Ok(point);
+            }
+            Ok(G1Projective::from(point).clear_cofactor().into())
+        }
+    }
+}
+
+/// Decompresses a G1 point without the subgroup check
+fn g1_affine_from_bytes_unchecked(bytes: &Bytes48) -> Result<G1Affine, KzgError> {
+    // 48 bytes can only be the compressed encoding, which must carry the compression flag
+    if bytes.as_slice()[0] & 0x80 == 0 {
+        return Err(KzgError::BadArgs(
+            "The compression flag of the G1 point is not set".to_string(),
+        ));
+    }
+
+    Option::from(G1Affine::from_compressed_unchecked(&(bytes.clone().into()))).ok_or_else(|| {
+        KzgError::NotOnCurve("The bytes do not encode a point on the G1 curve".to_string())
+    })
+}
 
-    let scalar = Scalar::from_bytes(&lendian);
-    if scalar.is_none().into() {
+/// Decompresses a G2 point, with the same errors as [`safe_g1_affine_from_bytes`]
+pub fn safe_g2_affine_from_bytes(bytes: &Bytes96) -> Result<G2Affine, KzgError> {
+    if bytes.as_slice()[0] & 0x80 == 0 {
         return Err(KzgError::BadArgs(
-            "Failed to parse G1Affine from bytes".to_string(),
+            "The compression flag of the G2 point is not set".to_string(),
+        ));
+    }
+
+    let point: G2Affine =
+        Option::from(G2Affine::from_compressed_unchecked(&(bytes.clone().into()))).ok_or_else(
+            || KzgError::NotOnCurve("The bytes do not encode a point on the G2 curve".to_string()),
+        )?;
+    if !bool::from(point.is_torsion_free()) {
+        return Err(KzgError::NotInSubgroup(
+            "The G2 point is not in the prime-order subgroup".to_string(),
         ));
     }
-    Ok(scalar.unwrap())
+    Ok(point)
+}
+
+/// Returns `true` if `commitment` decompresses to a point in the G1 prime-order subgroup.
+///
+/// This never errors and runs no pairing, so it can be used as a cheap pre-filter for
+/// incoming commitments before any proof is checked.
+pub fn is_valid_commitment(commitment: &Bytes48) -> bool {
+    safe_g1_affine_from_bytes(commitment).is_ok()
+}
+
+pub fn safe_scalar_affine_from_bytes(bytes: &Bytes32) -> Result<Scalar, KzgError> {
+    parse_field_element(bytes, Endianness::Big)
+}
+
+/// Parses a canonical field element encoded with the given byte order
+///
+/// Returns [`KzgError::BadFieldElement`] if the encoded integer is not below the modulus, so
+/// no caller ever computes with a silently reduced value.
+pub fn parse_field_element(bytes: &Bytes32, endianness: Endianness) -> Result<Scalar, KzgError> {
+    let mut lendian: [u8; 32] = bytes.clone().into();
+    if endianness == Endianness::Big {
+        lendian.reverse();
+    }
+
+    Option::from(Scalar::from_bytes(&lendian)).ok_or_else(|| {
+        KzgError::BadFieldElement("The field element is not below the BLS modulus".to_string())
+    })
+}
+
+/// Encodes a field element with the given byte order, the inverse of [`parse_field_element`]
+pub fn scalar_to_blob_bytes(scalar: &Scalar, endianness: Endianness) -> Bytes32 {
+    let mut bytes = scalar.to_bytes();
+    if endianness == Endianness::Big {
+        bytes.reverse();
+    }
+    bytes.into()
+}
+
+/// Incrementally derives the Fiat-Shamir challenge of [`compute_challenge`], so the blob can be
+/// streamed into the hasher instead of being copied into one `CHALLENGE_INPUT_SIZE` buffer.
+///
+/// The hashed bytes are laid out exactly as in [`compute_challenge`]: the domain separator, the
+/// polynomial degree (16 bytes, big-endian), the blob and finally the compressed commitment.
+#[derive(Debug, Clone)]
+pub struct ChallengeHasher {
+    hasher: Sha256,
+    blob_bytes_written: usize,
+}
+
+impl Default for ChallengeHasher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ChallengeHasher {
+    pub fn new() -> Self {
+        Self::with_domain(DEFAULT_FIAT_SHAMIR_DOMAIN)
+    }
+
+    /// Same as [`Self::new`], with another domain separator than
+    /// [`FIAT_SHAMIR_PROTOCOL_DOMAIN`](crate::FIAT_SHAMIR_PROTOCOL_DOMAIN)
+    pub fn with_domain(domain: [u8; DOMAIN_STR_LENGTH]) -> Self {
+        let mut hasher = Sha256::new();
+        // Domain separator
+        hasher.update(domain);
+        // Polynomial degree (16-bytes, big-endian)
+        hasher.update(0_u64.to_be_bytes());
+        hasher.update((NUM_FIELD_ELEMENTS_PER_BLOB as u64).to_be_bytes());
+
+        Self {
+            hasher,
+            blob_bytes_written: 0,
+        }
+    }
+
+    /// Feeds the next chunk of the blob, chunks can be of any size
+    pub fn update(&mut self, blob_bytes: &[u8]) -> Result<(), KzgError> {
+        if self.blob_bytes_written + blob_bytes.len() > BYTES_PER_BLOB {
+            return Err(KzgError::InvalidBytesLength(format!(
+                "The blob should be {} bytes long, but got at least {}",
+                BYTES_PER_BLOB,
+                self.blob_bytes_written + blob_bytes.len(),
+            )));
+        }
+        self.hasher.update(blob_bytes);
+        self.blob_bytes_written += blob_bytes.len();
+        Ok(())
+    }
+
+    /// Appends the commitment and returns the challenge, once the whole blob has been fed
+    pub fn finalize(mut self, commitment: &G1Affine) -> Result<Scalar, KzgError> {
+        if self.blob_bytes_written != BYTES_PER_BLOB {
+            return Err(KzgError::InvalidBytesLength(format!(
+                "The blob should be {} bytes long, but was {}",
+                BYTES_PER_BLOB, self.blob_bytes_written,
+            )));
+        }
+        self.hasher.update(commitment.to_compressed());
+
+        let evaluation: [u8; 32] = self.hasher.finalize().into();
+        Ok(scalar_from_bytes_unchecked(evaluation))
+    }
 }
 
 /// Return the Fiat-Shamir challenge required to verify `blob` and `commitment`.
 pub fn compute_challenge(blob: &Blob, commitment: &G1Affine) -> Result<Scalar, KzgError> {
-    let mut bytes = [0_u8; CHALLENGE_INPUT_SIZE];
-    let mut offset = 0_usize;
-    // Copy domain separator
-    bytes[offset..DOMAIN_STR_LENGTH].copy_from_slice(FIAT_SHAMIR_PROTOCOL_DOMAIN.as_bytes());
-    offset += DOMAIN_STR_LENGTH;
-    // Copy polynomial degree (16-bytes, big-endian)
-    bytes[offset..offset + 8].copy_from_slice(&0_u64.to_be_bytes());
-    offset += 8;
-    bytes[offset..offset + 8].copy_from_slice(&(NUM_FIELD_ELEMENTS_PER_BLOB as u64).to_be_bytes());
-    offset += 8;
-    // Copy blob
-    bytes[offset..offset + BYTES_PER_BLOB].copy_from_slice(blob.as_slice());
-    offset += BYTES_PER_BLOB;
-    // Copy commitment
-    bytes[offset..offset + BYTES_PER_COMMITMENT].copy_from_slice(&commitment.to_compressed());
-    offset += BYTES_PER_COMMITMENT;
-    /* Make sure we wrote the entire buffer */
-    if offset != CHALLENGE_INPUT_SIZE {
-        return Err(KzgError::InvalidBytesLength(format!(
-            "The challenge should be {} length, but was {}",
-            CHALLENGE_INPUT_SIZE, offset,
-        )));
-    }
-    let evaluation: [u8; 32] = Sha256::digest(bytes).into();
-    Ok(scalar_from_bytes_unchecked(evaluation))
+    compute_challenge_with_domain(blob, commitment, DEFAULT_FIAT_SHAMIR_DOMAIN)
+}
+
+/// Same as [`compute_challenge`], with the domain separator of a forked protocol, see
+/// [`KzgSettings::fiat_shamir_domain`]
+pub fn compute_challenge_with_domain(
+    blob: &Blob,
+    commitment: &G1Affine,
+    domain: [u8; DOMAIN_STR_LENGTH],
+) -> Result<Scalar, KzgError> {
+    let mut hasher = ChallengeHasher::with_domain(domain);
+    hasher.update(blob.as_slice())?;
+    hasher.finalize(commitment)
 }
 
 pub fn scalar_from_bytes_unchecked(bytes: [u8; 32]) -> Scalar {
@@ -91,61 +235,113 @@ pub fn scalar_from_u64_array_unchecked(array: [u64; 4]) -> Scalar {
 }
 
 /// Evaluates a polynomial in evaluation form at a given point
+///
+/// Uses the barycentric formula
+/// \( p(x) = \frac{x^n - 1}{n} \sum_i \frac{p_i \omega_i}{x - \omega_i} \)
+/// where all the \( (x - \omega_i)^{-1} \) terms come from a single batch inversion, so the
+/// whole evaluation costs exactly one field inversion.
 pub fn evaluate_polynomial_in_evaluation_form(
     polynomial: Vec<Scalar>,
     x: Scalar,
     kzg_settings: &KzgSettings,
 ) -> Result<Scalar, KzgError> {
-    if polynomial.len() != NUM_FIELD_ELEMENTS_PER_BLOB {
-        return Err(KzgError::InvalidBytesLength(
-            "The polynomial length is incorrect".to_string(),
-        ));
+    let n = kzg_settings.field_elements_per_blob();
+    if polynomial.len() != n {
+        return Err(KzgError::MismatchedLengths {
+            expected: n,
+            got: polynomial.len(),
+        });
     }
 
-    let mut inverses_in = vec![Scalar::default(); NUM_FIELD_ELEMENTS_PER_BLOB];
-    let mut inverses = vec![Scalar::default(); NUM_FIELD_ELEMENTS_PER_BLOB];
+    let mut inverses_in = vec![Scalar::default(); n];
+    let mut inverses = vec![Scalar::default(); n];
     let roots_of_unity = kzg_settings.roots_of_unity;
-    for i in 0..NUM_FIELD_ELEMENTS_PER_BLOB {
+    for i in 0..n {
         if x == roots_of_unity[i] {
             return Ok(polynomial[i]);
         }
         inverses_in[i] = x - roots_of_unity[i];
     }
 
-    batch_inversion(
-        &mut inverses,
-        &inverses_in,
-        NonZeroUsize::new(NUM_FIELD_ELEMENTS_PER_BLOB).unwrap(),
-    )?;
+    batch_inversion(&mut inverses, &inverses_in, NonZeroUsize::new(n).unwrap())?;
 
     let mut out = Scalar::zero();
 
-    for i in 0..NUM_FIELD_ELEMENTS_PER_BLOB {
+    for i in 0..n {
         out += (inverses[i] * roots_of_unity[i]) * polynomial[i];
     }
 
-    out *= Scalar::from(NUM_FIELD_ELEMENTS_PER_BLOB as u64)
-        .invert()
-        .unwrap();
-    out *= x.pow(&[NUM_FIELD_ELEMENTS_PER_BLOB as u64, 0, 0, 0]) - Scalar::one();
+    // The domain size is a power of two, so 1/n is a power of 1/2 and needs no inversion
+    let log2_n = n.trailing_zeros() as u64;
+    out *= Scalar::TWO_INV.pow_vartime(&[log2_n, 0, 0, 0]);
+    out *= x.pow_vartime(&[n as u64, 0, 0, 0]) - Scalar::one();
 
     Ok(out)
 }
 
+/// Interpolates the polynomial through `(index, value)` pairs, where `index` is a blob position,
+/// i.e. the domain point `roots_of_unity[index]`, and returns its full evaluation form. Every
+/// position must be given, in any order: the values go through an IFFT to the coefficients of
+/// the polynomial and an FFT back. Fewer positions leave the polynomial underdetermined.
+pub fn interpolate_on_domain(
+    values: &[(usize, Scalar)],
+    kzg_settings: &KzgSettings,
+) -> Result<Vec<Scalar>, KzgError> {
+    let n = kzg_settings.field_elements_per_blob();
+    if values.len() < n {
+        return Err(KzgError::BadArgs(format!(
+            "Interpolating over {} points is underdetermined with {} values",
+            n,
+            values.len()
+        )));
+    }
+    if values.len() > n {
+        return Err(KzgError::MismatchedLengths {
+            expected: n,
+            got: values.len(),
+        });
+    }
+
+    let mut evaluations = vec![None; n];
+    for &(index, value) in values {
+        let slot = evaluations.get_mut(index).ok_or_else(|| {
+            KzgError::BadArgs(format!(
+                "Index {} is out of range for {} field elements",
+                index, n
+            ))
+        })?;
+        if slot.is_some() {
+            return Err(KzgError::BadArgs(format!("Index {} is duplicated", index)));
+        }
+        *slot = Some(value);
+    }
+
+    // n distinct in-range indices fill every position. The FFTs run in natural order.
+    let mut evaluations = evaluations.into_iter().flatten().collect::<Vec<_>>();
+    let mut roots = kzg_settings.roots_of_unity.to_vec();
+    bit_reversal_permutation(&mut roots);
+    bit_reversal_permutation(&mut evaluations);
+    ifft(&mut evaluations, &roots)?;
+    fft(&mut evaluations, &roots)?;
+    bit_reversal_permutation(&mut evaluations);
+
+    Ok(evaluations)
+}
+
 /// Montgomery batch inversion in a finite field
 /// Given a list of elements \( x_1, x_2, \dots, x_n \) from a finite field \( F \), Montgomery batch inversion computes the inverses \( x_1^{-1}, x_2^{-1}, \dots, x_n^{-1} \) as follows:
 ///
 /// Let's consider three elements \( a \), \( b \), and \( c \) in a finite field \( F \). The steps are as follows:
 ///
 /// 1. **Product Accumulation**:
-///     \[
-///     P = a \times b \times c
-///     \]
+///    \[
+///    P = a \times b \times c
+///    \]
 ///
 /// 2. **Single Inversion**:
-///     \[
-///     P^{-1} = \text{inverse}(P)
-///     \]
+///    \[
+///    P^{-1} = \text{inverse}(P)
+///    \]
 ///
 /// 3. **Backward Substitution**:
 ///     - \( a^{-1} = P^{-1} \times (b \times c) \)
@@ -181,7 +377,8 @@ pub fn batch_inversion(out: &mut [Scalar], a: &[Scalar], len: NonZeroUsize) -> R
     // \[
     // P^{-1} = \text{inverse}(P)
     // \]
-    accumulator = accumulator.invert().unwrap();
+    accumulator = Option::from(accumulator.invert()).ok_or(KzgError::InternalError)?;
+    count_ops!(field_inversions += 1);
 
     // Compute the inverse of each element \( x_i^{-1} \) by using the precomputed product and its inverse:
     //
@@ -203,21 +400,96 @@ pub fn verify_kzg_proof_impl(
     proof: G1Affine,
     kzg_settings: &KzgSettings,
 ) -> Result<bool, KzgError> {
-    let x = G2Projective::generator() * z;
-    let x_minus_z = kzg_settings.g2_points[1] - x;
-
-    let y = G1Projective::generator() * y;
-    let p_minus_y = commitment - y;
+    verify_kzg_proof_impl_ct(commitment, z, y, proof, kzg_settings).map(bool::from)
+}
 
-    // Verify: P - y = Q * (X - z)
-    Ok(pairings_verify(
-        p_minus_y.into(),
-        G2Projective::generator().into(),
+pub fn verify_kzg_proof_impl_ct(
+    commitment: G1Affine,
+    z: Scalar,
+    y: Scalar,
+    proof: G1Affine,
+    kzg_settings: &KzgSettings,
+) -> Result<Choice, KzgError> {
+    Ok(verify_kzg_proof_with_stats(
+        commitment,
+        z,
+        y,
         proof,
-        x_minus_z.into(),
+        kzg_settings,
+        &mut VerifyStats::default(),
     ))
 }
 
+/// [`verify_kzg_proof_impl_ct`], recording the pairing check in `stats`
+fn verify_kzg_proof_with_stats(
+    commitment: G1Affine,
+    z: Scalar,
+    y: Scalar,
+    proof: G1Affine,
+    kzg_settings: &KzgSettings,
+    stats: &mut VerifyStats,
+) -> Choice {
+    // Verify: P - y = Q * (X - z), as e(P - y + z * Q, G2) = e(Q, X) so that both G2 points are
+    // fixed and prepared ahead of time
+    let p_minus_y_plus_z_q = commitment - G1Projective::generator() * y + proof * z;
+
+    count_ops!(g1_scalar_muls += 2);
+    verify_against_setup_ct(p_minus_y_plus_z_q.into(), proof, kzg_settings, stats)
+}
+
+/// Checks `e(a, G2) == e(b, [tau]G2)`, the shape every KZG check here comes down to, with one
+/// Miller loop on the prepared G2 points. Records the pairs and whether the `[tau]G2` prepared
+/// with the settings could be used in `stats`.
+fn verify_against_setup_ct(
+    a: G1Affine,
+    b: G1Affine,
+    kzg_settings: &KzgSettings,
+    stats: &mut VerifyStats,
+) -> Choice {
+    let tau_g2 = kzg_settings.tau_g2_for_miller_loop();
+    stats.used_precompute |= matches!(tau_g2, Cow::Borrowed(_));
+
+    count_ops!(multi_miller_loops += 1, final_exponentiations += 1);
+    stats.pairings += 2;
+    setup_pairing_check(a, b, &tau_g2).verify_ct()
+}
+
+/// The terms `e(-a, G2) * e(b, [tau]G2)` of [`verify_against_setup_ct`], on prepared G2 points
+fn setup_pairing_check(a: G1Affine, b: G1Affine, tau_g2: &G2Prepared) -> PairingCheck<'_, 2> {
+    PairingCheck::new()
+        .add_prepared_term(-a, prepared_g2_generator())
+        .add_prepared_term(b, tau_g2)
+}
+
+/// Diagnostic only, verification never relies on it: when `z` is a point of the domain, the
+/// opening of the polynomial there has to be the evaluation the blob stores at that position,
+/// which can be checked without any pairing. Errors on an inconsistent `y`, e.g. to catch a
+/// malformed test vector early, and accepts every `z` off the domain.
+pub fn check_on_domain_evaluation(
+    polynomial: &[Scalar],
+    z: &Scalar,
+    y: &Scalar,
+    kzg_settings: &KzgSettings,
+) -> Result<(), KzgError> {
+    let Some(index) = kzg_settings
+        .roots_of_unity
+        .iter()
+        .position(|root| root == z)
+    else {
+        return Ok(());
+    };
+    match polynomial.get(index) {
+        Some(evaluation) if evaluation == y => Ok(()),
+        Some(_) => Err(KzgError::BadArgs(format!(
+            "z is the domain point at position {}, but y differs from the evaluation stored there",
+            index
+        ))),
+        None => Err(KzgError::InvalidBytesLength(
+            "The polynomial length is incorrect".to_string(),
+        )),
+    }
+}
+
 pub fn validate_batched_input(commitment: &[G1Affine], proofs: &[G1Affine]) -> Result<(), KzgError> {
     // Check if any commitment is invalid (not on curve or identity)
     let invalid_commitment = commitment.iter().any(|commitment| {
@@ -241,32 +513,375 @@ pub fn validate_batched_input(commitment: &[G1Affine], proofs: &[G1Affine]) -> R
     Ok(()) // Return Ok if all commitments and proofs are valid
 }
 
+/// Runs the structural checks of a blob batch without any of its cryptography: matching
+/// lengths, commitments and proofs that decode to G1 points under the settings' subgroup policy,
+/// canonical blob field elements, and no (commitment, proof) pair given twice. Returns the first
+/// problem found, naming the offending entry.
+pub fn batch_preflight(
+    blobs: &[Blob],
+    commitments_bytes: &[Bytes48],
+    proofs_bytes: &[Bytes48],
+    kzg_settings: &KzgSettings,
+) -> Result<(), KzgError> {
+    if let Some(got) = [commitments_bytes.len(), proofs_bytes.len()]
+        .into_iter()
+        .find(|&len| len != blobs.len())
+    {
+        return Err(KzgError::MismatchedLengths {
+            expected: blobs.len(),
+            got,
+        });
+    }
+
+    let mut seen = BTreeMap::new();
+    for (i, ((blob, commitment_bytes), proof_bytes)) in blobs
+        .iter()
+        .zip(commitments_bytes)
+        .zip(proofs_bytes)
+        .enumerate()
+    {
+        g1_affine_from_bytes_with_policy(commitment_bytes, kzg_settings.subgroup_policy)
+            .map_err(|e| e.with_context(format_args!("Commitment {}", i)))?;
+        g1_affine_from_bytes_with_policy(proof_bytes, kzg_settings.subgroup_policy)
+            .map_err(|e| e.with_context(format_args!("Proof {}", i)))?;
+
+        for (j, bytes) in blob
+            .as_slice()
+            .chunks_exact(BYTES_PER_FIELD_ELEMENT)
+            .enumerate()
+        {
+            parse_field_element(
+                &Bytes32::from_slice(bytes)?,
+                kzg_settings.field_element_endianness,
+            )
+            .map_err(|_| {
+                KzgError::BadFieldElement(format!(
+                    "Field element {} of blob {} is not canonical",
+                    j, i
+                ))
+            })?;
+        }
+
+        if let Some(first) = seen.insert((commitment_bytes.as_slice(), proof_bytes.as_slice()), i) {
+            return Err(KzgError::BadArgs(format!(
+                "Entries {} and {} have the same commitment and proof",
+                first, i
+            )));
+        }
+    }
+
+    Ok(())
+}
+
 pub fn compute_challenges_and_evaluate_polynomial(
     blobs: Vec<Blob>,
     commitment: &[G1Affine],
     kzg_settings: &KzgSettings,
 ) -> Result<(Vec<Scalar>, Vec<Scalar>), KzgError> {
-    // Initialize vectors to store evaluation challenges and polynomial evaluations
-    let mut evaluation_challenges = Vec::with_capacity(blobs.len());
-    let mut ys = Vec::with_capacity(blobs.len());
-
-    // Iterate over each blob to compute its polynomial evaluation
-    for i in 0..blobs.len() {
-        // Convert the blob to its polynomial representation
-        let polynomial = blobs[i].as_polynomial()?;
-        // Compute the Fiat-Shamir challenge for the current blob and its commitment
-        let evaluation_challenge = compute_challenge(&blobs[i], &commitment[i])?;
-        // Evaluate the polynomial at the computed challenge
-        let y =
-            evaluate_polynomial_in_evaluation_form(polynomial, evaluation_challenge, kzg_settings)?;
+    challenges_and_evaluations(&blobs, commitment, kzg_settings)
+}
+
+/// [`compute_challenges_and_evaluate_polynomial`] on borrowed blobs
+fn challenges_and_evaluations(
+    blobs: &[Blob],
+    commitment: &[G1Affine],
+    kzg_settings: &KzgSettings,
+) -> Result<(Vec<Scalar>, Vec<Scalar>), KzgError> {
+    // The blobs are independent, so with the `parallel` feature each one is its own task
+    #[cfg(feature = "parallel")]
+    let entries = blobs.par_iter().zip(commitment.par_iter());
+    #[cfg(not(feature = "parallel"))]
+    let entries = blobs.iter().zip(commitment.iter());
+
+    let openings = entries
+        .map(|(blob, commitment)| {
+            // Convert the blob to its polynomial representation
+            let polynomial =
+                blob.as_polynomial_with_endianness(kzg_settings.field_element_endianness)?;
+            // Compute the Fiat-Shamir challenge for the current blob and its commitment
+            let evaluation_challenge =
+                compute_challenge_with_domain(blob, commitment, kzg_settings.fiat_shamir_domain)?;
+            // Evaluate the polynomial at the computed challenge
+            let y = evaluate_polynomial_in_evaluation_form(
+                polynomial,
+                evaluation_challenge,
+                kzg_settings,
+            )?;
+            Ok((evaluation_challenge, y))
+        })
+        .collect::<Vec<Result<_, KzgError>>>();
+
+    // Collected in order first, so the error of the first bad blob wins as in the serial path
+    let openings = openings.into_iter().collect::<Result<Vec<_>, _>>()?;
+    Ok(openings.into_iter().unzip())
+}
+
+/// Commits to a polynomial in evaluation form using the Lagrange G1 points of the setup
+pub fn poly_to_kzg_commitment(
+    polynomial: &[Scalar],
+    kzg_settings: &KzgSettings,
+) -> Result<G1Affine, KzgError> {
+    if polynomial.len() != kzg_settings.field_elements_per_blob() {
+        return Err(KzgError::MismatchedLengths {
+            expected: kzg_settings.field_elements_per_blob(),
+            got: polynomial.len(),
+        });
+    }
+
+    count_ops!(g1_scalar_muls += polynomial.len());
+    Ok(g1_lincomb_with_window(
+        kzg_settings.g1_points,
+        polynomial,
+        kzg_settings.msm_window(),
+    )
+    .into())
+}
+
+/// Computes the KZG commitment of a blob, i.e. the MSM of its field elements with the Lagrange
+/// G1 points of the setup, compressed to 48 bytes
+pub fn blob_to_kzg_commitment(
+    blob: &Blob,
+    kzg_settings: &KzgSettings,
+) -> Result<Bytes48, KzgError> {
+    let polynomial = blob.as_polynomial_with_endianness(kzg_settings.field_element_endianness)?;
+    let commitment = poly_to_kzg_commitment(&polynomial, kzg_settings)?;
+    Ok(commitment.to_compressed().into())
+}
+
+/// Debugging aid for blobs whose field elements may have been laid out in natural order instead
+/// of the bit-reversed order of the setup's domain. Returns `true` when the blob does not commit
+/// to `commitment` as it is, but does once its field elements are bit-reversed.
+///
+/// This is only a heuristic: commitments carry no notion of distance, so any other mistake in
+/// the blob or the commitment, or a malformed blob, also gives `false`. It costs two commitments.
+pub fn likely_natural_order(blob: &Blob, commitment: &Bytes48, kzg_settings: &KzgSettings) -> bool {
+    let commits_to = |blob: &Blob| {
+        blob_to_kzg_commitment(blob, kzg_settings).is_ok_and(|computed| computed == *commitment)
+    };
+    if commits_to(blob) {
+        return false;
+    }
+
+    let mut field_elements = blob.to_field_element_bytes();
+    bit_reversal_permutation(&mut field_elements);
+    Blob::from_field_element_bytes(&field_elements).is_ok_and(|reordered| commits_to(&reordered))
+}
+
+/// EIP-4844 versioned hash of a commitment, i.e. its SHA-256 digest with the first byte
+/// replaced by [`VERSIONED_HASH_VERSION_KZG`]
+pub fn kzg_commitment_to_versioned_hash(commitment: &KzgCommitment) -> [u8; 32] {
+    let mut hash: [u8; 32] = Sha256::digest(commitment.as_bytes().as_slice()).into();
+    hash[0] = VERSIONED_HASH_VERSION_KZG;
+    hash
+}
+
+/// Commits to a blob and derives the versioned hash of the commitment in one go, as needed to
+/// build a sidecar
+pub fn blob_to_commitment_and_hash(
+    blob: &Blob,
+    kzg_settings: &KzgSettings,
+) -> Result<(KzgCommitment, [u8; 32]), KzgError> {
+    let commitment = KzgCommitment::from(blob_to_kzg_commitment(blob, kzg_settings)?);
+    let versioned_hash = kzg_commitment_to_versioned_hash(&commitment);
+    Ok((commitment, versioned_hash))
+}
+
+/// Opens a blob at `z`, returning the compressed proof and the big-endian evaluation `y = p(z)`.
+///
+/// The blob is the evaluation form of `p` over the roots of unity of the setup, which derive
+/// from `SCALE2_ROOT_OF_UNITY`. `z` may be one of those roots, see [`compute_kzg_proof_impl`].
+pub fn compute_kzg_proof(
+    blob: &Blob,
+    z_bytes: &Bytes32,
+    kzg_settings: &KzgSettings,
+) -> Result<(Bytes48, Bytes32), KzgError> {
+    let z = safe_scalar_affine_from_bytes(z_bytes)?;
+    let polynomial = blob.as_polynomial_with_endianness(kzg_settings.field_element_endianness)?;
+    let (proof, y) = compute_kzg_proof_impl(&polynomial, z, kzg_settings)?;
+    Ok((
+        proof.to_compressed().into(),
+        scalar_to_blob_bytes(&y, Endianness::Big),
+    ))
+}
+
+/// Computes the proof that [`KzgProof::verify_blob_kzg_proof`] checks, i.e. the opening of the
+/// blob at the Fiat-Shamir challenge derived from the blob and its commitment
+pub fn compute_blob_kzg_proof(
+    blob: &Blob,
+    commitment_bytes: &Bytes48,
+    kzg_settings: &KzgSettings,
+) -> Result<Bytes48, KzgError> {
+    let commitment =
+        g1_affine_from_bytes_with_policy(commitment_bytes, kzg_settings.subgroup_policy)
+            .map_err(|e| e.with_context("The commitment is not a valid G1 point"))?;
+    let polynomial = blob.as_polynomial_with_endianness(kzg_settings.field_element_endianness)?;
+    let evaluation_challenge =
+        compute_challenge_with_domain(blob, &commitment, kzg_settings.fiat_shamir_domain)?;
+    let (proof, _) = compute_kzg_proof_impl(&polynomial, evaluation_challenge, kzg_settings)?;
+    Ok(proof.to_compressed().into())
+}
+
+/// Packs proofs for storage as their 48-byte compressed encodings, back to back
+pub fn pack_proofs(proofs: &[Bytes48]) -> Vec<u8> {
+    proofs
+        .iter()
+        .flat_map(|proof| proof.as_slice())
+        .copied()
+        .collect()
+}
+
+/// Splits bytes from [`pack_proofs`] back into proofs. Only the length is checked, the points
+/// get decompressed and validated when verified.
+pub fn unpack_proofs(bytes: &[u8]) -> Result<Vec<Bytes48>, KzgError> {
+    if !bytes.len().is_multiple_of(BYTES_PER_PROOF) {
+        return Err(KzgError::InvalidBytesLength(format!(
+            "Packed proofs must be a multiple of {} bytes, got {}",
+            BYTES_PER_PROOF,
+            bytes.len()
+        )));
+    }
+    bytes
+        .chunks_exact(BYTES_PER_PROOF)
+        .map(Bytes48::try_from)
+        .collect()
+}
+
+/// Commits to the polynomial whose only nonzero evaluation is `value` at blob position `index`,
+/// i.e. `value * g1_points[index]`
+pub fn commit_single(
+    index: usize,
+    value: &Scalar,
+    kzg_settings: &KzgSettings,
+) -> Result<KzgCommitment, KzgError> {
+    let point = kzg_settings.g1_points.get(index).ok_or_else(|| {
+        KzgError::BadArgs(format!(
+            "Index {} is out of range for {} field elements",
+            index,
+            kzg_settings.g1_points.len()
+        ))
+    })?;
+
+    count_ops!(g1_scalar_muls += 1);
+    Ok(G1Affine::from(point * value).into())
+}
+
+/// Commits to the element-wise difference of two blobs, which by linearity is
+/// `commitment(a) - commitment(b)`, using a single MSM
+pub fn commitment_delta(
+    a: &Blob,
+    b: &Blob,
+    kzg_settings: &KzgSettings,
+) -> Result<KzgCommitment, KzgError> {
+    let a = a.as_polynomial_with_endianness(kzg_settings.field_element_endianness)?;
+    let b = b.as_polynomial_with_endianness(kzg_settings.field_element_endianness)?;
+    let delta = a.iter().zip(&b).map(|(a, b)| a - b).collect::<Vec<_>>();
+
+    Ok(poly_to_kzg_commitment(&delta, kzg_settings)?.into())
+}
+
+/// Builds a blob commitment one field element at a time, for producers that generate the blob
+/// on the fly and don't want to buffer it. Adding `value` at `index` accumulates
+/// `value * g1_points[index]`, so positions that are never added count as zero and positions
+/// added twice are summed.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CommitmentAccumulator {
+    accumulator: G1Projective,
+}
+
+impl CommitmentAccumulator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add_element(
+        &mut self,
+        index: usize,
+        value: &Scalar,
+        kzg_settings: &KzgSettings,
+    ) -> Result<(), KzgError> {
+        let point = kzg_settings.g1_points.get(index).ok_or_else(|| {
+            KzgError::BadArgs(format!(
+                "Index {} is out of range for {} field elements",
+                index,
+                kzg_settings.g1_points.len()
+            ))
+        })?;
+
+        count_ops!(g1_scalar_muls += 1);
+        self.accumulator += point * value;
+        Ok(())
+    }
+
+    pub fn finish(self) -> KzgCommitment {
+        G1Affine::from(self.accumulator).into()
+    }
+}
+
+/// Computes the opening proof of a polynomial in evaluation form at `z`, returning the proof
+/// together with the evaluation `y = p(z)`.
+///
+/// The quotient \( q_i = \frac{p_i - y}{\omega_i - z} \) needs the same inverses as the
+/// barycentric evaluation of `y`, so both come out of a single batch inversion. When `z` is the
+/// domain point \( \omega_m \), \( q_m \) is instead the limit
+/// \( \sum_{i \neq m} \frac{(p_i - y) \omega_i}{z (z - \omega_i)} \).
+pub fn compute_kzg_proof_impl(
+    polynomial: &[Scalar],
+    z: Scalar,
+    kzg_settings: &KzgSettings,
+) -> Result<(G1Affine, Scalar), KzgError> {
+    let n = kzg_settings.field_elements_per_blob();
+    if polynomial.len() != n {
+        return Err(KzgError::MismatchedLengths {
+            expected: n,
+            got: polynomial.len(),
+        });
+    }
+
+    let roots_of_unity = kzg_settings.roots_of_unity;
+    let domain_index = roots_of_unity.iter().position(|root| *root == z);
+
+    let mut denominators = roots_of_unity
+        .iter()
+        .map(|root| root - z)
+        .collect::<Vec<_>>();
+    // The zero denominator is swapped for z, whose inverse the limit of q_m needs anyway
+    if let Some(m) = domain_index {
+        denominators[m] = z;
+    }
+    let mut inverses = vec![Scalar::default(); n];
+    batch_inversion(&mut inverses, &denominators, NonZeroUsize::new(n).unwrap())?;
 
-        // Store the evaluation challenge and the polynomial evaluation
-        evaluation_challenges.push(evaluation_challenge);
-        ys.push(y);
+    let y = match domain_index {
+        Some(m) => polynomial[m],
+        None => {
+            let sum = polynomial
+                .iter()
+                .zip(roots_of_unity)
+                .zip(&inverses)
+                .fold(Scalar::zero(), |acc, ((p, root), inverse)| {
+                    acc + p * root * inverse
+                });
+            let log2_n = n.trailing_zeros() as u64;
+            -sum * Scalar::TWO_INV.pow_vartime(&[log2_n, 0, 0, 0])
+                * (z.pow_vartime(&[n as u64, 0, 0, 0]) - Scalar::one())
+        }
+    };
+
+    let mut quotient = polynomial
+        .iter()
+        .zip(&inverses)
+        .map(|(p, inverse)| (p - y) * inverse)
+        .collect::<Vec<_>>();
+    if let Some(m) = domain_index {
+        // quotient[m] is still zero here, so it drops out of the sum
+        let sum = quotient
+            .iter()
+            .zip(roots_of_unity)
+            .fold(Scalar::zero(), |acc, (q, root)| acc + q * root);
+        quotient[m] = -sum * inverses[m];
     }
 
-    // Return the vectors of evaluation challenges and polynomial evaluations
-    Ok((evaluation_challenges, ys))
+    Ok((poly_to_kzg_commitment(&quotient, kzg_settings)?, y))
 }
 
 pub fn compute_powers(base: &Scalar, num_powers: usize) -> Vec<Scalar> {
@@ -281,26 +896,41 @@ pub fn compute_powers(base: &Scalar, num_powers: usize) -> Vec<Scalar> {
     powers
 }
 
-pub fn compute_r_powers(
+/// Derives the random scalar of the batch verification's linear combination.
+///
+/// The scalar is the Fiat-Shamir hash of every input, with the same layout as c-kzg:
+/// `batch domain || field elements per blob || n`, both as big-endian
+/// `u64`s, followed by `commitment || z || y || proof` for each opening, with `z` and `y`
+/// big-endian. The batch domain is [`KzgSettings::batch_domain`], i.e.
+/// `RANDOM_CHALLENGE_KZG_BATCH_DOMAIN` unless overridden. No entropy source is involved, so
+/// identical inputs always give the same scalar.
+pub fn compute_batch_challenge(
     commitment: &[G1Affine],
     zs: &[Scalar],
     ys: &[Scalar],
     proofs: &[G1Affine],
-) -> Result<Vec<Scalar>, KzgError> {
+    kzg_settings: &KzgSettings,
+) -> Result<Scalar, KzgError> {
     let n = commitment.len();
+    if let Some(got) = [zs.len(), ys.len(), proofs.len()]
+        .into_iter()
+        .find(|&len| len != n)
+    {
+        return Err(KzgError::MismatchedLengths { expected: n, got });
+    }
+
     let input_size =
         32 + n * (BYTES_PER_COMMITMENT + 2 * BYTES_PER_FIELD_ELEMENT + BYTES_PER_PROOF);
 
     let mut bytes: Vec<u8> = vec![0; input_size];
 
     // Copy domain separator
-    bytes[..16].copy_from_slice(RANDOM_CHALLENGE_KZG_BATCH_DOMAIN.as_bytes());
+    bytes[..16].copy_from_slice(&kzg_settings.batch_domain);
 
-    bytes[16..24].copy_from_slice(&(NUM_FIELD_ELEMENTS_PER_BLOB as u64).to_be_bytes());
+    bytes[16..24].copy_from_slice(&(kzg_settings.field_elements_per_blob() as u64).to_be_bytes());
 
-    let mut n_bytes = n.to_be_bytes().to_vec();
-    n_bytes.resize(8, 0);
-    bytes[24..32].copy_from_slice(&n_bytes);
+    // Always 8 bytes, whatever the target's pointer width
+    bytes[24..32].copy_from_slice(&(n as u64).to_be_bytes());
 
     let mut offset = 32;
 
@@ -311,13 +941,13 @@ pub fn compute_r_powers(
         offset += BYTES_PER_COMMITMENT;
 
         // Copy evaluation challenge
-        let v = zs[i].to_bytes();
-        bytes[offset..(v.len() + offset)].copy_from_slice(&v[..]);
+        let v = scalar_to_blob_bytes(&zs[i], Endianness::Big);
+        bytes[offset..(v.as_slice().len() + offset)].copy_from_slice(v.as_slice());
         offset += BYTES_PER_FIELD_ELEMENT;
 
         // Copy polynomial's evaluation value
-        let v = ys[i].to_bytes();
-        bytes[offset..(v.len() + offset)].copy_from_slice(&v[..]);
+        let v = scalar_to_blob_bytes(&ys[i], Endianness::Big);
+        bytes[offset..(v.as_slice().len() + offset)].copy_from_slice(v.as_slice());
         offset += BYTES_PER_FIELD_ELEMENT;
 
         // Copy proof
@@ -335,15 +965,217 @@ pub fn compute_r_powers(
 
     // Now let's create the challenge!
     let evaluation: [u8; 32] = Sha256::digest(bytes).into();
-    let r = scalar_from_bytes_unchecked(evaluation);
+    Ok(scalar_from_bytes_unchecked(evaluation))
+}
 
-    Ok(compute_powers(&r, n))
+/// Powers of the scalar from [`compute_batch_challenge`], one per opening
+pub fn compute_r_powers(
+    commitment: &[G1Affine],
+    zs: &[Scalar],
+    ys: &[Scalar],
+    proofs: &[G1Affine],
+    kzg_settings: &KzgSettings,
+) -> Result<Vec<Scalar>, KzgError> {
+    let r = compute_batch_challenge(commitment, zs, ys, proofs, kzg_settings)?;
+    Ok(compute_powers(&r, commitment.len()))
 }
 
-pub struct KzgProof {}
+/// Computes the two G1 aggregates the batch pairing check runs on, see
+/// [`KzgProof::verify_blob_kzg_proof_batch_with_aggregates`]
+pub fn compute_batch_aggregates(
+    commitments: &[G1Affine],
+    zs: &[Scalar],
+    ys: &[Scalar],
+    proofs: &[G1Affine],
+    kzg_settings: &KzgSettings,
+) -> Result<(G1Affine, G1Affine), KzgError> {
+    // Compute r powers
+    let r_powers = compute_r_powers(commitments, zs, ys, proofs, kzg_settings)?;
 
-impl KzgProof {
-    pub fn verify_kzg_proof(
+    let (proof_lincomb, rhs_g1) = compute_batch_lincombs(commitments, zs, ys, proofs, &r_powers);
+    Ok((proof_lincomb.into(), rhs_g1.into()))
+}
+
+/// `msm_variable_base`, split into one chunk per rayon thread with the `parallel` feature.
+/// Group addition is exact, so the sum is the same point either way.
+fn g1_msm(points: &[G1Projective], scalars: &[Scalar]) -> G1Projective {
+    #[cfg(feature = "parallel")]
+    {
+        let chunk_size = points.len().div_ceil(rayon::current_num_threads()).max(1);
+        points
+            .par_chunks(chunk_size)
+            .zip(scalars.par_chunks(chunk_size))
+            .map(|(points, scalars)| G1Projective::msm_variable_base(points, scalars))
+            .sum()
+    }
+    #[cfg(not(feature = "parallel"))]
+    G1Projective::msm_variable_base(points, scalars)
+}
+
+/// Decompresses the commitments or proofs of a batch, concurrently with the `parallel` feature.
+/// Either way the error of the first bad entry is the one returned.
+fn g1_affines_from_bytes(
+    bytes: &[Bytes48],
+    policy: SubgroupPolicy,
+) -> Result<Vec<G1Affine>, KzgError> {
+    #[cfg(feature = "parallel")]
+    let bytes = bytes.par_iter();
+    #[cfg(not(feature = "parallel"))]
+    let bytes = bytes.iter();
+
+    let points = bytes
+        .map(|bytes| g1_affine_from_bytes_with_policy(bytes, policy))
+        .collect::<Vec<_>>();
+    points.into_iter().collect()
+}
+
+/// The aggregates of [`compute_batch_aggregates`] for a run of openings, given their powers of
+/// `r`. Aggregates of consecutive runs add up to the aggregates of the whole batch.
+fn compute_batch_lincombs(
+    commitments: &[G1Affine],
+    zs: &[Scalar],
+    ys: &[Scalar],
+    proofs: &[G1Affine],
+    r_powers: &[Scalar],
+) -> (G1Projective, G1Projective) {
+    let n = commitments.len();
+
+    // Convert proofs to G1Projective
+    let proofs = proofs.iter().map(Into::into).collect::<Vec<_>>();
+
+    // Compute proof linear combination
+    let proof_lincomb = g1_msm(&proofs, r_powers);
+
+    // Compute c_minus_y and r_times_z
+    #[cfg(feature = "parallel")]
+    let indices = (0..n).into_par_iter();
+    #[cfg(not(feature = "parallel"))]
+    let indices = 0..n;
+    let (c_minus_y, r_times_z): (Vec<G1Projective>, Vec<Scalar>) = indices
+        .map(|i| {
+            let ys_encrypted = G1Affine::generator() * ys[i];
+            (commitments[i] - ys_encrypted, r_powers[i] * zs[i])
+        })
+        .unzip();
+
+    // Compute proof_z_lincomb and c_minus_y_lincomb
+    let proof_z_lincomb = g1_msm(&proofs, &r_times_z);
+    let c_minus_y_lincomb = g1_msm(&c_minus_y, r_powers);
+
+    // Compute rhs_g1
+    let rhs_g1 = c_minus_y_lincomb + proof_z_lincomb;
+    count_ops!(g1_scalar_muls += 4 * n);
+
+    (proof_lincomb, rhs_g1)
+}
+
+/// Which paths a verification took, see [`KzgProof::verify_blob_kzg_proof_batch_verbose`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct VerifyStats {
+    /// The pairing check used the `[tau]G2` prepared along with the settings, rather than
+    /// preparing it on the spot
+    pub used_precompute: bool,
+    /// The blobs were evaluated and aggregated on the rayon pool, see the `parallel` feature
+    pub used_rayon: bool,
+    /// Pairs fed to the Miller loop
+    pub pairings: usize,
+}
+
+/// A blob reduced to everything its proof verification needs except the proof itself, for
+/// blobs that are verified again and again in overlapping batches, e.g. in a mempool. See
+/// [`KzgProof::verify_prepared_batch`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PreparedBlob {
+    polynomial: Vec<Scalar>,
+    commitment: G1Affine,
+    challenge: Scalar,
+    evaluation: Scalar,
+}
+
+impl PreparedBlob {
+    /// Parses the blob and commitment, and computes the Fiat-Shamir challenge and the
+    /// evaluation of the blob at it
+    pub fn new(
+        blob: &Blob,
+        commitment_bytes: &Bytes48,
+        kzg_settings: &KzgSettings,
+    ) -> Result<Self, KzgError> {
+        let commitment =
+            g1_affine_from_bytes_with_policy(commitment_bytes, kzg_settings.subgroup_policy)?;
+        let polynomial =
+            blob.as_polynomial_with_endianness(kzg_settings.field_element_endianness)?;
+        let challenge =
+            compute_challenge_with_domain(blob, &commitment, kzg_settings.fiat_shamir_domain)?;
+        let evaluation =
+            evaluate_polynomial_in_evaluation_form(polynomial.clone(), challenge, kzg_settings)?;
+
+        Ok(Self {
+            polynomial,
+            commitment,
+            challenge,
+            evaluation,
+        })
+    }
+
+    pub fn polynomial(&self) -> &[Scalar] {
+        &self.polynomial
+    }
+
+    pub fn commitment(&self) -> &G1Affine {
+        &self.commitment
+    }
+
+    pub fn challenge(&self) -> &Scalar {
+        &self.challenge
+    }
+
+    /// The polynomial evaluated at [`Self::challenge`]
+    pub fn evaluation(&self) -> &Scalar {
+        &self.evaluation
+    }
+}
+
+pub struct KzgProof {}
+
+impl KzgProof {
+    /// Opens one blob at several points, parsing the blob only once. Returns the compressed
+    /// proof and the big-endian evaluation for each of `zs`, in order.
+    ///
+    /// With the `parallel` feature the openings are computed concurrently.
+    pub fn compute_kzg_proofs_multi(
+        blob: &Blob,
+        zs: &[Bytes32],
+        kzg_settings: &KzgSettings,
+    ) -> Result<Vec<(Bytes48, Bytes32)>, KzgError> {
+        let polynomial =
+            blob.as_polynomial_with_endianness(kzg_settings.field_element_endianness)?;
+        let zs = zs
+            .iter()
+            .map(safe_scalar_affine_from_bytes)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        #[cfg(feature = "parallel")]
+        let zs = zs.par_iter();
+        #[cfg(not(feature = "parallel"))]
+        let zs = zs.iter();
+
+        zs.map(|z| {
+            let (proof, y) = compute_kzg_proof_impl(&polynomial, *z, kzg_settings)?;
+            Ok((
+                proof.to_compressed().into(),
+                scalar_to_blob_bytes(&y, Endianness::Big),
+            ))
+        })
+        .collect()
+    }
+
+    /// Verifies a single opening with one Miller loop and one final exponentiation. When the G2
+    /// points were prepared along with the settings, i.e. unless they were built with
+    /// `precompute(false)` or `g2_points` was overwritten since, this never allocates on the
+    /// success path and is suited to tight loops on targets without a cheap allocator. Otherwise
+    /// `[tau]G2` is prepared, and allocated, on every call; [`Self::verify_kzg_proof_fast`]
+    /// rejects such settings instead.
+    pub fn verify_kzg_proof(
         commitment_bytes: &Bytes48,
         z_bytes: &Bytes32,
         y_bytes: &Bytes32,
@@ -362,78 +1194,177 @@ impl KzgProof {
                 return Err(e);
             }
         };
-        let commitment = match safe_g1_affine_from_bytes(commitment_bytes) {
-            Ok(g1) => g1,
-            Err(e) => {
-                return Err(e);
-            }
-        };
-        let proof = match safe_g1_affine_from_bytes(proof_bytes) {
+        let commitment = match g1_affine_from_bytes_with_policy(
+            commitment_bytes,
+            kzg_settings.subgroup_policy,
+        ) {
             Ok(g1) => g1,
             Err(e) => {
                 return Err(e);
             }
         };
+        let proof =
+            match g1_affine_from_bytes_with_policy(proof_bytes, kzg_settings.subgroup_policy) {
+                Ok(g1) => g1,
+                Err(e) => {
+                    return Err(e);
+                }
+            };
 
-        let g2_x = G2Affine::generator() * z;
-        let x_minus_z = kzg_settings.g2_points[1] - g2_x;
+        verify_kzg_proof_impl(commitment, z, y, proof, kzg_settings)
+    }
+
+    /// [`Self::verify_kzg_proof`] for latency-critical callers: errors with
+    /// [`KzgError::SetupNotPrecomputed`] instead of preparing `[tau]G2` on the spot when the
+    /// settings were built with `precompute(false)` or their G2 points were overwritten since.
+    pub fn verify_kzg_proof_fast(
+        commitment_bytes: &Bytes48,
+        z_bytes: &Bytes32,
+        y_bytes: &Bytes32,
+        proof_bytes: &Bytes48,
+        kzg_settings: &KzgSettings,
+    ) -> Result<bool, KzgError> {
+        if kzg_settings.prepared_tau_g2().is_none() {
+            return Err(KzgError::SetupNotPrecomputed);
+        }
+        Self::verify_kzg_proof(
+            commitment_bytes,
+            z_bytes,
+            y_bytes,
+            proof_bytes,
+            kzg_settings,
+        )
+    }
+
+    /// Same as [`Self::verify_kzg_proof`] without a [`KzgSettings`]: the only setup points an
+    /// opening needs, `[tau]_2` and the G2 generator, are passed in directly. Every point is
+    /// subgroup checked, and both G2 points are prepared for the Miller loop on every call.
+    pub fn verify_kzg_proof_standalone(
+        commitment_bytes: &Bytes48,
+        z_bytes: &Bytes32,
+        y_bytes: &Bytes32,
+        proof_bytes: &Bytes48,
+        tau_g2_bytes: &Bytes96,
+        g2_gen_bytes: &Bytes96,
+    ) -> Result<bool, KzgError> {
+        let z = safe_scalar_affine_from_bytes(z_bytes)?;
+        let y = safe_scalar_affine_from_bytes(y_bytes)?;
+        let commitment = safe_g1_affine_from_bytes(commitment_bytes)?;
+        let proof = safe_g1_affine_from_bytes(proof_bytes)?;
+        let tau_g2 = safe_g2_affine_from_bytes(tau_g2_bytes)?;
+        let g2_gen = safe_g2_affine_from_bytes(g2_gen_bytes)?;
 
-        let g1_y = G1Affine::generator() * y;
-        let p_minus_y = commitment - g1_y;
+        let x_minus_z = tau_g2 - g2_gen * z;
+        let p_minus_y = commitment - G1Affine::generator() * y;
 
+        count_ops!(
+            g1_scalar_muls += 1,
+            multi_miller_loops += 1,
+            final_exponentiations += 1
+        );
         Ok(pairings_verify(
             p_minus_y.into(),
-            G2Affine::generator(),
+            g2_gen,
             proof,
             x_minus_z.into(),
         ))
     }
 
-    pub fn verify_kzg_proof_batch(
-        commitments: &[G1Affine],
-        zs: &[Scalar],
-        ys: &[Scalar],
-        proofs: &[G1Affine],
+    /// Adds the Miller loop of this proof's check to `acc` instead of finishing it, so callers
+    /// can batch it with other pairing checks under a single final exponentiation.
+    ///
+    /// The proof is valid iff its terms alone finalize to `Gt::identity()`. Once several checks
+    /// are accumulated, the identity only says that the product of all of them holds: a failing
+    /// check can be cancelled by another one crafted against it, so the caller has to weigh each
+    /// check with a random scalar, like [`Self::verify_blob_kzg_proof_batch`] does, unless every
+    /// input is trusted. Start from `MillerLoopResult::default()`, the neutral element.
+    pub fn verify_kzg_proof_accumulate(
+        commitment_bytes: &Bytes48,
+        z_bytes: &Bytes32,
+        y_bytes: &Bytes32,
+        proof_bytes: &Bytes48,
+        acc: &mut MillerLoopResult,
         kzg_settings: &KzgSettings,
-    ) -> Result<bool, KzgError> {
-        let n = commitments.len();
+    ) -> Result<(), KzgError> {
+        let z = safe_scalar_affine_from_bytes(z_bytes)?;
+        let y = safe_scalar_affine_from_bytes(y_bytes)?;
+        let commitment =
+            g1_affine_from_bytes_with_policy(commitment_bytes, kzg_settings.subgroup_policy)?;
+        let proof = g1_affine_from_bytes_with_policy(proof_bytes, kzg_settings.subgroup_policy)?;
 
-        // Initialize vectors to store intermediate values
-        let mut c_minus_y: Vec<G1Projective> = Vec::with_capacity(n);
-        let mut r_times_z: Vec<Scalar> = Vec::with_capacity(n);
+        let p_minus_y_plus_z_q =
+            G1Affine::from(commitment - G1Projective::generator() * y + proof * z);
+        let tau_g2 = kzg_settings.tau_g2_for_miller_loop();
 
-        // Compute r powers
-        let r_powers = compute_r_powers(commitments, zs, ys, proofs)?;
+        // Same terms as `verify_kzg_proof`: e(-(P - y + z * proof), G2) * e(proof, X)
+        count_ops!(g1_scalar_muls += 2, multi_miller_loops += 1);
+        *acc += setup_pairing_check(p_minus_y_plus_z_q, proof, &tau_g2).miller_loop();
+        Ok(())
+    }
 
-        // Convert proofs to G1Projective
-        let proofs = proofs.iter().map(Into::into).collect::<Vec<_>>();
+    /// Reference version of [`Self::verify_kzg_proof`] that checks
+    /// \( e(C - [y]G_1, G_2) = e(\pi, [\tau - z]G_2) \) literally, with two separate pairings and
+    /// an equality of the results. Only meant for cross-checking the optimized pairing checks.
+    #[cfg(feature = "reference")]
+    pub fn verify_kzg_proof_two_pairings(
+        commitment_bytes: &Bytes48,
+        z_bytes: &Bytes32,
+        y_bytes: &Bytes32,
+        proof_bytes: &Bytes48,
+        kzg_settings: &KzgSettings,
+    ) -> Result<bool, KzgError> {
+        use bls12_381::pairing;
 
-        // Compute proof linear combination
-        let proof_lincomb = G1Projective::msm_variable_base(&proofs, &r_powers);
+        let z = safe_scalar_affine_from_bytes(z_bytes)?;
+        let y = safe_scalar_affine_from_bytes(y_bytes)?;
+        let commitment =
+            g1_affine_from_bytes_with_policy(commitment_bytes, kzg_settings.subgroup_policy)?;
+        let proof = g1_affine_from_bytes_with_policy(proof_bytes, kzg_settings.subgroup_policy)?;
 
-        // Compute c_minus_y and r_times_z
-        for i in 0..n {
-            let ys_encrypted = G1Affine::generator() * ys[i];
-            c_minus_y.push(commitments[i] - ys_encrypted);
-            r_times_z.push(r_powers[i] * zs[i]);
-        }
+        let x_minus_z = G2Affine::from(kzg_settings.g2_points[1] - G2Affine::generator() * z);
+        let p_minus_y = G1Affine::from(commitment - G1Affine::generator() * y);
 
-        // Compute proof_z_lincomb and c_minus_y_lincomb
-        let proof_z_lincomb = G1Projective::msm_variable_base(&proofs, &r_times_z);
-        let c_minus_y_lincomb = G1Projective::msm_variable_base(&c_minus_y, &r_powers);
+        Ok(pairing(&p_minus_y, &G2Affine::generator()) == pairing(&proof, &x_minus_z))
+    }
 
-        // Compute rhs_g1
-        let rhs_g1 = c_minus_y_lincomb + proof_z_lincomb;
+    pub fn verify_kzg_proof_batch(
+        commitments: &[G1Affine],
+        zs: &[Scalar],
+        ys: &[Scalar],
+        proofs: &[G1Affine],
+        kzg_settings: &KzgSettings,
+    ) -> Result<bool, KzgError> {
+        let (aggregated_proof, aggregated_commitment_minus_eval) =
+            compute_batch_aggregates(commitments, zs, ys, proofs, kzg_settings)?;
 
-        // Verify the pairing equation
-        let result = pairings_verify(
-            proof_lincomb.into(),
-            kzg_settings.g2_points[1],
-            rhs_g1.into(),
-            G2Affine::generator(),
-        );
+        Ok(Self::verify_blob_kzg_proof_batch_with_aggregates(
+            aggregated_proof,
+            aggregated_commitment_minus_eval,
+            kzg_settings,
+        ))
+    }
 
-        Ok(result)
+    /// Runs only the final pairing check of the batch verification, on aggregates computed by
+    /// the caller, e.g. with an MSM offloaded to a coprocessor. With `r` from
+    /// [`compute_batch_challenge`], the expected quantities are
+    ///
+    /// - `aggregated_proof`: \( \sum_i r^i \pi_i \)
+    /// - `aggregated_commitment_minus_eval`: \( \sum_i r^i (C_i - [y_i]G_1 + z_i \pi_i) \)
+    ///
+    /// and the check is \( e(\text{aggregated\_proof}, [\tau]G_2) =
+    /// e(\text{aggregated\_commitment\_minus\_eval}, G_2) \). See [`compute_batch_aggregates`].
+    pub fn verify_blob_kzg_proof_batch_with_aggregates(
+        aggregated_proof: G1Affine,
+        aggregated_commitment_minus_eval: G1Affine,
+        kzg_settings: &KzgSettings,
+    ) -> bool {
+        verify_against_setup_ct(
+            aggregated_commitment_minus_eval,
+            aggregated_proof,
+            kzg_settings,
+            &mut VerifyStats::default(),
+        )
+        .into()
     }
 
     pub fn verify_blob_kzg_proof(
@@ -442,225 +1373,640 @@ impl KzgProof {
         proof_bytes: &Bytes48,
         kzg_settings: &KzgSettings,
     ) -> Result<bool, KzgError> {
+        Self::verify_blob_kzg_proof_ct(blob, commitment_bytes, proof_bytes, kzg_settings)
+            .map(bool::from)
+    }
+
+    /// Same as [`Self::verify_blob_kzg_proof`], but returns the verification result as a
+    /// [`Choice`] so callers can keep their control flow constant-time. Malformed inputs are
+    /// still reported through the `Err` variant.
+    pub fn verify_blob_kzg_proof_ct(
+        blob: Blob,
+        commitment_bytes: &Bytes48,
+        proof_bytes: &Bytes48,
+        kzg_settings: &KzgSettings,
+    ) -> Result<Choice, KzgError> {
+        Self::verify_blob_kzg_proof_with_challenge_ct(
+            &blob,
+            commitment_bytes,
+            proof_bytes,
+            kzg_settings,
+            &mut VerifyStats::default(),
+        )
+        .map(|(valid, _)| valid)
+    }
+
+    /// Same as [`Self::verify_blob_kzg_proof`], but also returns the Fiat-Shamir challenge `z`
+    /// the blob was opened at, for proof systems that bind it into a larger transcript.
+    pub fn verify_blob_kzg_proof_with_transcript(
+        blob: Blob,
+        commitment_bytes: &Bytes48,
+        proof_bytes: &Bytes48,
+        kzg_settings: &KzgSettings,
+    ) -> Result<(bool, Scalar), KzgError> {
+        Self::verify_blob_kzg_proof_with_challenge_ct(
+            &blob,
+            commitment_bytes,
+            proof_bytes,
+            kzg_settings,
+            &mut VerifyStats::default(),
+        )
+        .map(|(valid, evaluation_challenge)| (valid.into(), evaluation_challenge))
+    }
+
+    fn verify_blob_kzg_proof_with_challenge_ct(
+        blob: &Blob,
+        commitment_bytes: &Bytes48,
+        proof_bytes: &Bytes48,
+        kzg_settings: &KzgSettings,
+        stats: &mut VerifyStats,
+    ) -> Result<(Choice, Scalar), KzgError> {
         // Convert commitment bytes to G1Affine
-        let commitment = safe_g1_affine_from_bytes(commitment_bytes)?;
+        let commitment =
+            g1_affine_from_bytes_with_policy(commitment_bytes, kzg_settings.subgroup_policy)?;
 
         // Convert blob to polynomial
-        let polynomial = blob.as_polynomial()?;
+        let polynomial =
+            blob.as_polynomial_with_endianness(kzg_settings.field_element_endianness)?;
 
         // Convert proof bytes to G1Affine
-        let proof = safe_g1_affine_from_bytes(proof_bytes)?;
+        let proof = g1_affine_from_bytes_with_policy(proof_bytes, kzg_settings.subgroup_policy)?;
 
         // Compute the evaluation challenge for the blob and commitment
-        let evaluation_challenge = compute_challenge(&blob, &commitment)?;
+        let evaluation_challenge =
+            compute_challenge_with_domain(blob, &commitment, kzg_settings.fiat_shamir_domain)?;
 
         // Evaluate the polynomial in evaluation form
         let y =
             evaluate_polynomial_in_evaluation_form(polynomial, evaluation_challenge, kzg_settings)?;
 
         // Verify the KZG proof
-        verify_kzg_proof_impl(commitment, evaluation_challenge, y, proof, kzg_settings)
+        let valid = verify_kzg_proof_with_stats(
+            commitment,
+            evaluation_challenge,
+            y,
+            proof,
+            kzg_settings,
+            stats,
+        );
+        Ok((valid, evaluation_challenge))
+    }
+
+    /// Same as [`Self::verify_blob_kzg_proof`], but also recomputes the commitment from the blob
+    /// and errors if it differs from `commitment_bytes`.
+    ///
+    /// A passing blob proof already binds the commitment to the blob, but only through the
+    /// Fiat-Shamir argument. This check removes that reliance and catches a commitment that was
+    /// substituted for the blob's own, e.g. by whoever assembled a sidecar. It is not required
+    /// by the spec and costs an extra 4096-point MSM on top of the regular verification.
+    pub fn verify_blob_kzg_proof_strict(
+        blob: Blob,
+        commitment_bytes: &Bytes48,
+        proof_bytes: &Bytes48,
+        kzg_settings: &KzgSettings,
+    ) -> Result<bool, KzgError> {
+        let commitment =
+            g1_affine_from_bytes_with_policy(commitment_bytes, kzg_settings.subgroup_policy)?;
+        let polynomial =
+            blob.as_polynomial_with_endianness(kzg_settings.field_element_endianness)?;
+
+        if poly_to_kzg_commitment(&polynomial, kzg_settings)? != commitment {
+            return Err(KzgError::BadArgs(
+                "The commitment does not match the blob".to_string(),
+            ));
+        }
+
+        Self::verify_blob_kzg_proof(blob, commitment_bytes, proof_bytes, kzg_settings)
     }
 
+    /// Verifies every blob proof at once through a random linear combination. An empty batch is
+    /// valid, and a batch of one is checked directly with [`Self::verify_blob_kzg_proof`].
+    ///
+    /// Batches can be padded to a fixed size, e.g. for a circuit, with dummy entries made of
+    /// [`Blob::zero`], [`KzgCommitment::identity`] and the identity as proof: the zero
+    /// polynomial evaluates to zero everywhere, so such an entry always verifies and leaves the
+    /// result of the real entries unchanged.
+    ///
+    /// With the `parallel` feature the points are decompressed, the blobs evaluated and the
+    /// aggregates summed on the rayon pool. The pairing check and the result stay the same.
     pub fn verify_blob_kzg_proof_batch(
         blobs: Vec<Blob>,
         commitments_bytes: Vec<Bytes48>,
         proofs_bytes: Vec<Bytes48>,
         kzg_settings: &KzgSettings,
     ) -> Result<bool, KzgError> {
-        if blobs.is_empty() {
-            return Ok(true);
-        }
+        Self::verify_blob_kzg_proof_batch_with_stats(
+            &blobs,
+            &commitments_bytes,
+            &proofs_bytes,
+            kzg_settings,
+        )
+        .map(|(valid, _)| valid)
+    }
 
-        if blobs.len() == 1 {
-            return Self::verify_blob_kzg_proof(
-                blobs[0].clone(),
-                &commitments_bytes[0],
-                &proofs_bytes[0],
-                kzg_settings,
-            );
-        }
+    /// [`Self::verify_blob_kzg_proof_batch`] on borrowed entries, recording the paths it takes
+    /// as it goes. Callers holding on to their blobs, such as [`crate::BlobSidecar`], use it to
+    /// avoid copying them.
+    pub(crate) fn verify_blob_kzg_proof_batch_with_stats(
+        blobs: &[Blob],
+        commitments_bytes: &[Bytes48],
+        proofs_bytes: &[Bytes48],
+        kzg_settings: &KzgSettings,
+    ) -> Result<(bool, VerifyStats), KzgError> {
+        let mut stats = VerifyStats::default();
 
         if blobs.len() != commitments_bytes.len() {
-            return Err(KzgError::InvalidBytesLength(
-                "Invalid commitments length".to_string(),
-            ));
+            return Err(KzgError::MismatchedLengths {
+                expected: blobs.len(),
+                got: commitments_bytes.len(),
+            });
         }
 
         if blobs.len() != proofs_bytes.len() {
-            return Err(KzgError::InvalidBytesLength(
-                "Invalid proofs length".to_string(),
-            ));
+            return Err(KzgError::MismatchedLengths {
+                expected: blobs.len(),
+                got: proofs_bytes.len(),
+            });
         }
 
-        let commitments = commitments_bytes
-            .iter()
-            .map(safe_g1_affine_from_bytes)
-            .collect::<Result<Vec<_>, _>>()?;
+        if blobs.is_empty() {
+            return Ok((true, stats));
+        }
 
-        let proofs = proofs_bytes
-            .iter()
-            .map(safe_g1_affine_from_bytes)
-            .collect::<Result<Vec<_>, _>>()?;
+        // A lone entry gains nothing from the random linear combination
+        if blobs.len() == 1 {
+            let (valid, _) = Self::verify_blob_kzg_proof_with_challenge_ct(
+                &blobs[0],
+                &commitments_bytes[0],
+                &proofs_bytes[0],
+                kzg_settings,
+                &mut stats,
+            )?;
+            return Ok((valid.into(), stats));
+        }
+
+        // Decompressing, evaluating and aggregating go through rayon with `parallel`
+        stats.used_rayon = cfg!(feature = "parallel");
+        let commitments = g1_affines_from_bytes(commitments_bytes, kzg_settings.subgroup_policy)?;
+        let proofs = g1_affines_from_bytes(proofs_bytes, kzg_settings.subgroup_policy)?;
 
         validate_batched_input(&commitments, &proofs)?;
 
         let (evaluation_challenges, ys) =
-            compute_challenges_and_evaluate_polynomial(blobs, &commitments, kzg_settings)?;
+            challenges_and_evaluations(blobs, &commitments, kzg_settings)?;
 
-        Self::verify_kzg_proof_batch(
+        let (aggregated_proof, aggregated_commitment_minus_eval) = compute_batch_aggregates(
             &commitments,
             &evaluation_challenges,
             &ys,
             &proofs,
             kzg_settings,
-        )
+        )?;
+        let valid = verify_against_setup_ct(
+            aggregated_commitment_minus_eval,
+            aggregated_proof,
+            kzg_settings,
+            &mut stats,
+        );
+        Ok((valid.into(), stats))
     }
-}
 
-#[cfg(test)]
-pub mod tests {
-    use super::*;
-    use crate::test_files::{
-        VERIFY_BLOB_KZG_PROOF_BATCH_TESTS, VERIFY_BLOB_KZG_PROOF_TESTS, VERIFY_KZG_PROOF_TESTS,
-    };
-    use serde_derive::Deserialize;
+    /// Same as [`Self::verify_blob_kzg_proof_batch`], but pulls the entries from an async
+    /// stream. Each blob is parsed, challenged and evaluated as soon as it arrives and then
+    /// dropped, the random linear combination and the pairing run once the stream ends.
+    ///
+    /// Only the IO is async, the cryptography runs synchronously on the polling task.
+    #[cfg(feature = "futures")]
+    pub async fn verify_blob_stream<S>(
+        stream: S,
+        kzg_settings: &KzgSettings,
+    ) -> Result<bool, KzgError>
+    where
+        S: Stream<Item = (Blob, Bytes48, Bytes48)>,
+    {
+        let mut stream = core::pin::pin!(stream);
+        let mut commitments = Vec::new();
+        let mut evaluation_challenges = Vec::new();
+        let mut ys = Vec::new();
+        let mut proofs = Vec::new();
 
-    trait FromHex {
-        fn from_hex(hex: &str) -> Result<Self, KzgError>
-        where
-            Self: Sized;
-    }
+        while let Some((blob, commitment_bytes, proof_bytes)) = stream.next().await {
+            let commitment =
+                g1_affine_from_bytes_with_policy(&commitment_bytes, kzg_settings.subgroup_policy)?;
+            let proof =
+                g1_affine_from_bytes_with_policy(&proof_bytes, kzg_settings.subgroup_policy)?;
 
-    fn hex_to_bytes(hex_str: &str) -> Result<Vec<u8>, KzgError> {
-        let trimmed_str = hex_str.strip_prefix("0x").unwrap_or(hex_str);
-        hex::decode(trimmed_str)
-            .map_err(|e| KzgError::InvalidHexFormat(format!("Failed to decode hex: {}", e)))
-    }
+            let polynomial =
+                blob.as_polynomial_with_endianness(kzg_settings.field_element_endianness)?;
+            let evaluation_challenge =
+                compute_challenge_with_domain(&blob, &commitment, kzg_settings.fiat_shamir_domain)?;
+            let y = evaluate_polynomial_in_evaluation_form(
+                polynomial,
+                evaluation_challenge,
+                kzg_settings,
+            )?;
 
-    impl FromHex for Bytes48 {
-        fn from_hex(hex_str: &str) -> Result<Self, KzgError> {
-            Self::from_slice(&hex_to_bytes(hex_str).unwrap())
+            commitments.push(commitment);
+            evaluation_challenges.push(evaluation_challenge);
+            ys.push(y);
+            proofs.push(proof);
         }
+
+        Self::verify_evaluated_batch(
+            &commitments,
+            &evaluation_challenges,
+            &ys,
+            &proofs,
+            kzg_settings,
+        )
     }
 
-    impl FromHex for Bytes32 {
-        fn from_hex(hex_str: &str) -> Result<Self, KzgError> {
-            Self::from_slice(&hex_to_bytes(hex_str).unwrap())
-        }
+    /// Same as [`Self::verify_blob_kzg_proof_batch`], also reporting which paths it took, e.g. to
+    /// confirm a deployment runs the optimized ones
+    pub fn verify_blob_kzg_proof_batch_verbose(
+        blobs: Vec<Blob>,
+        commitments_bytes: Vec<Bytes48>,
+        proofs_bytes: Vec<Bytes48>,
+        kzg_settings: &KzgSettings,
+    ) -> Result<(bool, VerifyStats), KzgError> {
+        Self::verify_blob_kzg_proof_batch_with_stats(
+            &blobs,
+            &commitments_bytes,
+            &proofs_bytes,
+            kzg_settings,
+        )
     }
 
-    impl FromHex for Blob {
-        fn from_hex(hex_str: &str) -> Result<Self, KzgError> {
-            Self::from_slice(&hex_to_bytes(hex_str).unwrap())
+    /// Same as [`Self::verify_blob_kzg_proof_batch`], but an empty batch is rejected with
+    /// [`KzgError::Empty`] instead of passing, for callers that treat it as a bug.
+    pub fn verify_blob_kzg_proof_batch_nonempty(
+        blobs: Vec<Blob>,
+        commitments_bytes: Vec<Bytes48>,
+        proofs_bytes: Vec<Bytes48>,
+        kzg_settings: &KzgSettings,
+    ) -> Result<bool, KzgError> {
+        if blobs.is_empty() && commitments_bytes.is_empty() && proofs_bytes.is_empty() {
+            return Err(KzgError::Empty);
         }
-    }
 
-    #[derive(Debug, Deserialize)]
-    pub struct Input<'a> {
-        commitment: &'a str,
-        z: &'a str,
-        y: &'a str,
-        proof: &'a str,
+        Self::verify_blob_kzg_proof_batch(blobs, commitments_bytes, proofs_bytes, kzg_settings)
     }
 
-    impl Input<'_> {
-        pub fn get_commitment(&self) -> Result<Bytes48, KzgError> {
-            Bytes48::from_hex(self.commitment)
+    /// Same as [`Self::verify_blob_kzg_proof_batch`], for very large batches: the blobs are split
+    /// into chunks of `chunk_size` entries, each chunk is evaluated and folded into partial
+    /// aggregates on the rayon pool, and the partials are summed in chunk order before the
+    /// single pairing check.
+    #[cfg(feature = "parallel")]
+    pub fn verify_blob_kzg_proof_batch_chunked(
+        blobs: Vec<Blob>,
+        commitments_bytes: Vec<Bytes48>,
+        proofs_bytes: Vec<Bytes48>,
+        chunk_size: usize,
+        kzg_settings: &KzgSettings,
+    ) -> Result<bool, KzgError> {
+        if blobs.len() != commitments_bytes.len() {
+            return Err(KzgError::MismatchedLengths {
+                expected: blobs.len(),
+                got: commitments_bytes.len(),
+            });
         }
 
-        pub fn get_z(&self) -> Result<Bytes32, KzgError> {
-            Bytes32::from_hex(self.z)
+        if blobs.len() != proofs_bytes.len() {
+            return Err(KzgError::MismatchedLengths {
+                expected: blobs.len(),
+                got: proofs_bytes.len(),
+            });
         }
 
-        pub fn get_y(&self) -> Result<Bytes32, KzgError> {
-            Bytes32::from_hex(self.y)
+        if chunk_size == 0 {
+            return Err(KzgError::BadArgs(
+                "The chunk size must be positive".to_string(),
+            ));
         }
 
-        pub fn get_proof(&self) -> Result<Bytes48, KzgError> {
-            Bytes48::from_hex(self.proof)
+        let n = blobs.len();
+        if n == 0 {
+            return Ok(true);
         }
-    }
 
-    #[derive(Debug, Deserialize)]
-    pub struct Test<I> {
-        pub input: I,
-        output: Option<bool>,
-    }
+        let chunks = (0..n)
+            .step_by(chunk_size)
+            .map(|start| start..n.min(start + chunk_size))
+            .collect::<Vec<_>>();
 
-    impl<I> Test<I> {
-        pub fn get_output(&self) -> Option<bool> {
-            self.output
+        // Reduce every blob to its opening, one chunk per task
+        let openings = chunks
+            .par_iter()
+            .map(|chunk| {
+                blobs[chunk.clone()]
+                    .iter()
+                    .zip(&commitments_bytes[chunk.clone()])
+                    .zip(&proofs_bytes[chunk.clone()])
+                    .map(|((blob, commitment_bytes), proof_bytes)| {
+                        let commitment = g1_affine_from_bytes_with_policy(
+                            commitment_bytes,
+                            kzg_settings.subgroup_policy,
+                        )?;
+                        let proof = g1_affine_from_bytes_with_policy(
+                            proof_bytes,
+                            kzg_settings.subgroup_policy,
+                        )?;
+                        let polynomial = blob
+                            .as_polynomial_with_endianness(kzg_settings.field_element_endianness)?;
+                        let z = compute_challenge_with_domain(
+                            blob,
+                            &commitment,
+                            kzg_settings.fiat_shamir_domain,
+                        )?;
+                        let y =
+                            evaluate_polynomial_in_evaluation_form(polynomial, z, kzg_settings)?;
+                        Ok((commitment, z, y, proof))
+                    })
+                    .collect::<Result<Vec<_>, KzgError>>()
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut commitments = Vec::with_capacity(n);
+        let mut zs = Vec::with_capacity(n);
+        let mut ys = Vec::with_capacity(n);
+        let mut proofs = Vec::with_capacity(n);
+        for (commitment, z, y, proof) in openings.into_iter().flatten() {
+            commitments.push(commitment);
+            zs.push(z);
+            ys.push(y);
+            proofs.push(proof);
         }
-    }
 
-    #[test]
-    pub fn test_verify_kzg_proof() {
-        let kzg_settings = KzgSettings::load_trusted_setup_file().unwrap();
-        let test_files = VERIFY_KZG_PROOF_TESTS;
+        validate_batched_input(&commitments, &proofs)?;
+        let r_powers = compute_r_powers(&commitments, &zs, &ys, &proofs, kzg_settings)?;
 
-        for (_test_file, data) in test_files {
-            let test: Test<Input> = serde_yaml::from_str(data).unwrap();
-            let (Ok(commitment), Ok(z), Ok(y), Ok(proof)) = (
-                test.input.get_commitment(),
-                test.input.get_z(),
-                test.input.get_y(),
-                test.input.get_proof(),
-            ) else {
-                assert!(test.get_output().is_none());
-                continue;
-            };
+        // Fold each chunk into partial aggregates, then add them up in chunk order
+        let partials = chunks
+            .par_iter()
+            .map(|chunk| {
+                compute_batch_lincombs(
+                    &commitments[chunk.clone()],
+                    &zs[chunk.clone()],
+                    &ys[chunk.clone()],
+                    &proofs[chunk.clone()],
+                    &r_powers[chunk.clone()],
+                )
+            })
+            .collect::<Vec<_>>();
+        let (aggregated_proof, aggregated_commitment_minus_eval) = partials.into_iter().fold(
+            (G1Projective::identity(), G1Projective::identity()),
+            |(proof_acc, rhs_acc), (proof, rhs)| (proof_acc + proof, rhs_acc + rhs),
+        );
 
-            let result = KzgProof::verify_kzg_proof(&commitment, &z, &y, &proof, &kzg_settings);
-            match result {
-                Ok(result) => {
-                    assert_eq!(result, test.get_output().unwrap_or(false));
-                }
-                Err(_) => {
-                    assert!(test.get_output().is_none());
-                }
-            }
-        }
+        Ok(Self::verify_blob_kzg_proof_batch_with_aggregates(
+            aggregated_proof.into(),
+            aggregated_commitment_minus_eval.into(),
+            kzg_settings,
+        ))
     }
 
-    #[derive(Debug, Deserialize)]
-    pub struct BlobInput<'a> {
-        blob: &'a str,
-        commitment: &'a str,
-        proof: &'a str,
+    /// Same as [`Self::verify_blob_kzg_proof_batch`], but never clones a blob, and frees the
+    /// inputs as soon as every blob has been reduced to its opening, before the aggregation and
+    /// the pairing run.
+    pub fn verify_blob_kzg_proof_batch_owned(
+        blobs: Vec<Blob>,
+        commitments_bytes: Vec<Bytes48>,
+        proofs_bytes: Vec<Bytes48>,
+        kzg_settings: &KzgSettings,
+    ) -> Result<bool, KzgError> {
+        if blobs.len() != commitments_bytes.len() {
+            return Err(KzgError::MismatchedLengths {
+                expected: blobs.len(),
+                got: commitments_bytes.len(),
+            });
+        }
+
+        if blobs.len() != proofs_bytes.len() {
+            return Err(KzgError::MismatchedLengths {
+                expected: blobs.len(),
+                got: proofs_bytes.len(),
+            });
+        }
+
+        let n = blobs.len();
+        let mut commitments = Vec::with_capacity(n);
+        let mut evaluation_challenges = Vec::with_capacity(n);
+        let mut ys = Vec::with_capacity(n);
+        let mut proofs = Vec::with_capacity(n);
+
+        // Moving 128 KiB blobs out of the vector would only cost stack, iterate them in place
+        let entries = blobs.iter().zip(&commitments_bytes).zip(&proofs_bytes);
+        for ((blob, commitment_bytes), proof_bytes) in entries {
+            let commitment =
+                g1_affine_from_bytes_with_policy(commitment_bytes, kzg_settings.subgroup_policy)?;
+            let proof =
+                g1_affine_from_bytes_with_policy(proof_bytes, kzg_settings.subgroup_policy)?;
+
+            let polynomial =
+                blob.as_polynomial_with_endianness(kzg_settings.field_element_endianness)?;
+            let evaluation_challenge =
+                compute_challenge_with_domain(blob, &commitment, kzg_settings.fiat_shamir_domain)?;
+            let y = evaluate_polynomial_in_evaluation_form(
+                polynomial,
+                evaluation_challenge,
+                kzg_settings,
+            )?;
+
+            commitments.push(commitment);
+            evaluation_challenges.push(evaluation_challenge);
+            ys.push(y);
+            proofs.push(proof);
+        }
+        drop((blobs, commitments_bytes, proofs_bytes));
+
+        Self::verify_evaluated_batch(
+            &commitments,
+            &evaluation_challenges,
+            &ys,
+            &proofs,
+            kzg_settings,
+        )
     }
 
-    impl BlobInput<'_> {
-        pub fn get_blob(&self) -> Result<Blob, KzgError> {
-            Blob::from_hex(self.blob)
+    /// Same as [`Self::verify_blob_kzg_proof_batch`] for blobs prepared with
+    /// [`PreparedBlob::new`], so only the proofs are parsed and no blob is evaluated again
+    pub fn verify_prepared_batch(
+        prepared: &[PreparedBlob],
+        proofs_bytes: &[Bytes48],
+        kzg_settings: &KzgSettings,
+    ) -> Result<bool, KzgError> {
+        if prepared.len() != proofs_bytes.len() {
+            return Err(KzgError::MismatchedLengths {
+                expected: prepared.len(),
+                got: proofs_bytes.len(),
+            });
+        }
+
+        let proofs = proofs_bytes
+            .iter()
+            .map(|bytes| g1_affine_from_bytes_with_policy(bytes, kzg_settings.subgroup_policy))
+            .collect::<Result<Vec<_>, _>>()?;
+        let commitments = prepared
+            .iter()
+            .map(|blob| blob.commitment)
+            .collect::<Vec<_>>();
+        let evaluation_challenges = prepared
+            .iter()
+            .map(|blob| blob.challenge)
+            .collect::<Vec<_>>();
+        let ys = prepared
+            .iter()
+            .map(|blob| blob.evaluation)
+            .collect::<Vec<_>>();
+
+        Self::verify_evaluated_batch(
+            &commitments,
+            &evaluation_challenges,
+            &ys,
+            &proofs,
+            kzg_settings,
+        )
+    }
+
+    /// Final step of the batch verifiers, once every blob has been reduced to its opening
+    fn verify_evaluated_batch(
+        commitments: &[G1Affine],
+        evaluation_challenges: &[Scalar],
+        ys: &[Scalar],
+        proofs: &[G1Affine],
+        kzg_settings: &KzgSettings,
+    ) -> Result<bool, KzgError> {
+        match commitments.len() {
+            0 => Ok(true),
+            1 => verify_kzg_proof_impl(
+                commitments[0],
+                evaluation_challenges[0],
+                ys[0],
+                proofs[0],
+                kzg_settings,
+            ),
+            _ => {
+                validate_batched_input(commitments, proofs)?;
+                Self::verify_kzg_proof_batch(
+                    commitments,
+                    evaluation_challenges,
+                    ys,
+                    proofs,
+                    kzg_settings,
+                )
+            }
+        }
+    }
+
+    /// Same as [`Self::verify_blob_kzg_proof_batch`], but only the entries whose `mask` bit is
+    /// set are verified. Masked-out entries are dropped before the random linear combination is
+    /// computed, so they are neither parsed nor checked.
+    pub fn verify_blob_kzg_proof_batch_masked(
+        mut blobs: Vec<Blob>,
+        mut commitments_bytes: Vec<Bytes48>,
+        mut proofs_bytes: Vec<Bytes48>,
+        mask: &[bool],
+        kzg_settings: &KzgSettings,
+    ) -> Result<bool, KzgError> {
+        if blobs.len() != commitments_bytes.len() {
+            return Err(KzgError::MismatchedLengths {
+                expected: blobs.len(),
+                got: commitments_bytes.len(),
+            });
+        }
+
+        if blobs.len() != proofs_bytes.len() {
+            return Err(KzgError::MismatchedLengths {
+                expected: blobs.len(),
+                got: proofs_bytes.len(),
+            });
+        }
+
+        if blobs.len() != mask.len() {
+            return Err(KzgError::MismatchedLengths {
+                expected: blobs.len(),
+                got: mask.len(),
+            });
         }
 
+        // Lengths were checked above, so every entry has a matching mask bit
+        let mut selected = mask.iter();
+        blobs.retain(|_| *selected.next().unwrap());
+        let mut selected = mask.iter();
+        commitments_bytes.retain(|_| *selected.next().unwrap());
+        let mut selected = mask.iter();
+        proofs_bytes.retain(|_| *selected.next().unwrap());
+
+        Self::verify_blob_kzg_proof_batch(blobs, commitments_bytes, proofs_bytes, kzg_settings)
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::test_files::{
+        VERIFY_BLOB_KZG_PROOF_BATCH_TESTS, VERIFY_BLOB_KZG_PROOF_TESTS, VERIFY_KZG_PROOF_TESTS,
+    };
+    use crate::trusted_setup::generate_insecure_setup;
+    use crate::{FIAT_SHAMIR_PROTOCOL_DOMAIN, RANDOM_CHALLENGE_KZG_BATCH_DOMAIN};
+    use serde_derive::Deserialize;
+
+    #[derive(Debug, Deserialize)]
+    pub struct Input<'a> {
+        commitment: &'a str,
+        z: &'a str,
+        y: &'a str,
+        proof: &'a str,
+    }
+
+    impl Input<'_> {
         pub fn get_commitment(&self) -> Result<Bytes48, KzgError> {
             Bytes48::from_hex(self.commitment)
         }
 
+        pub fn get_z(&self) -> Result<Bytes32, KzgError> {
+            Bytes32::from_hex(self.z)
+        }
+
+        pub fn get_y(&self) -> Result<Bytes32, KzgError> {
+            Bytes32::from_hex(self.y)
+        }
+
         pub fn get_proof(&self) -> Result<Bytes48, KzgError> {
             Bytes48::from_hex(self.proof)
         }
     }
 
+    #[derive(Debug, Deserialize)]
+    pub struct Test<I> {
+        pub input: I,
+        output: Option<bool>,
+    }
+
+    impl<I> Test<I> {
+        pub fn get_output(&self) -> Option<bool> {
+            self.output
+        }
+    }
+
     #[test]
-    pub fn test_verify_blob_kzg_proof() {
+    pub fn test_verify_kzg_proof() {
         let kzg_settings = KzgSettings::load_trusted_setup_file().unwrap();
-        let test_files = VERIFY_BLOB_KZG_PROOF_TESTS;
+        let test_files = VERIFY_KZG_PROOF_TESTS;
 
         for (_test_file, data) in test_files {
-            let test: Test<BlobInput> = serde_yaml::from_str(data).unwrap();
-            let (Ok(blob), Ok(commitment), Ok(proof)) = (
-                test.input.get_blob(),
+            let test: Test<Input> = serde_yaml::from_str(data).unwrap();
+            let (Ok(commitment), Ok(z), Ok(y), Ok(proof)) = (
                 test.input.get_commitment(),
+                test.input.get_z(),
+                test.input.get_y(),
                 test.input.get_proof(),
             ) else {
                 assert!(test.get_output().is_none());
                 continue;
             };
 
-            let result = KzgProof::verify_blob_kzg_proof(blob, &commitment, &proof, &kzg_settings);
+            let result = KzgProof::verify_kzg_proof(&commitment, &z, &y, &proof, &kzg_settings);
             match result {
                 Ok(result) => {
                     assert_eq!(result, test.get_output().unwrap_or(false));
@@ -672,77 +2018,2352 @@ pub mod tests {
         }
     }
 
-    #[derive(Debug, Deserialize)]
-    struct BlobBatchInput<'a> {
-        #[serde(borrow)]
-        blob: &'a str,
-        #[serde(borrow)]
-        commitment: &'a str,
-        #[serde(borrow)]
-        proof: &'a str,
-    }
+    #[test]
+    pub fn test_verify_kzg_proof_standalone() {
+        let kzg_settings = KzgSettings::load_trusted_setup_file().unwrap();
+        let tau_g2 = Bytes96::from(kzg_settings.g2_points[1].to_compressed());
+        let g2_gen = Bytes96::from(kzg_settings.g2_points[0].to_compressed());
 
-    impl<'a> BlobBatchInput<'a> {
-        pub fn get_blobs(&self) -> Result<Blob, KzgError> {
-            Blob::from_hex(self.blob)
+        for (_test_file, data) in VERIFY_KZG_PROOF_TESTS {
+            let test: Test<Input> = serde_yaml::from_str(data).unwrap();
+            let (Ok(commitment), Ok(z), Ok(y), Ok(proof)) = (
+                test.input.get_commitment(),
+                test.input.get_z(),
+                test.input.get_y(),
+                test.input.get_proof(),
+            ) else {
+                continue;
+            };
+
+            let standalone = KzgProof::verify_kzg_proof_standalone(
+                &commitment,
+                &z,
+                &y,
+                &proof,
+                &tau_g2,
+                &g2_gen,
+            );
+            let with_settings =
+                KzgProof::verify_kzg_proof(&commitment, &z, &y, &proof, &kzg_settings);
+            assert_eq!(standalone.ok(), with_settings.ok());
         }
 
-        pub fn get_commitments(&self) -> Result<Bytes48, KzgError> {
-            Bytes48::from_hex(self.commitment)
+        // A G1 point is not a valid G2 encoding
+        let mut bad_g2 = [0u8; 96];
+        bad_g2[..48].copy_from_slice(&G1Affine::generator().to_compressed());
+        assert!(safe_g2_affine_from_bytes(&Bytes96::from(bad_g2)).is_err());
+    }
+
+    /// Counts the allocations of the current thread, other tests keep running concurrently
+    struct CountingAllocator;
+
+    std::thread_local! {
+        static ALLOCATIONS: std::cell::Cell<usize> = const { std::cell::Cell::new(0) };
+    }
+
+    #[allow(unsafe_code)]
+    unsafe impl std::alloc::GlobalAlloc for CountingAllocator {
+        unsafe fn alloc(&self, layout: std::alloc::Layout) -> *mut u8 {
+            let _ = ALLOCATIONS.try_with(|allocations| allocations.set(allocations.get() + 1));
+            std::alloc::System.alloc(layout)
         }
 
-        pub fn get_proofs(&self) -> Result<Bytes48, KzgError> {
-            Bytes48::from_hex(self.proof)
+        unsafe fn dealloc(&self, ptr: *mut u8, layout: std::alloc::Layout) {
+            std::alloc::System.dealloc(ptr, layout)
         }
     }
 
+    #[global_allocator]
+    static ALLOCATOR: CountingAllocator = CountingAllocator;
+
     #[test]
-    pub fn test_verify_blob_kzg_proof_batch() {
-        let test_files = VERIFY_BLOB_KZG_PROOF_BATCH_TESTS;
+    pub fn test_verify_kzg_proof_does_not_allocate() {
         let kzg_settings = KzgSettings::load_trusted_setup_file().unwrap();
+        // Preparing [tau]G2 allocates, but once, when the settings are built
+        let built = KzgSettings::builder()
+            .g1_lagrange(kzg_settings.g1_points)
+            .g2_monomial(kzg_settings.g2_points)
+            .build()
+            .unwrap();
+        let lean = KzgSettings::builder()
+            .g1_lagrange(kzg_settings.g1_points)
+            .g2_monomial(kzg_settings.g2_points)
+            .precompute(false)
+            .build()
+            .unwrap();
+        // The prepared G2 generator is shared by every setup and set up on first use
+        prepared_g2_generator();
 
-        for (_test_file, data) in test_files {
-            let test: Test<BlobBatchInput> = serde_yaml::from_str(data).unwrap();
-            let (Ok(blobs), Ok(commitments), Ok(proofs)) = (
-                test.input.get_blobs(),
-                test.input.get_commitments(),
-                test.input.get_proofs(),
+        let mut checked = 0;
+        for (_test_file, data) in VERIFY_KZG_PROOF_TESTS.iter().take(16) {
+            let test: Test<Input> = serde_yaml::from_str(data).unwrap();
+            let (Ok(commitment), Ok(z), Ok(y), Ok(proof), Some(output)) = (
+                test.input.get_commitment(),
+                test.input.get_z(),
+                test.input.get_y(),
+                test.input.get_proof(),
+                test.get_output(),
             ) else {
-                assert!(test.get_output().is_none());
                 continue;
             };
 
-            let result = KzgProof::verify_blob_kzg_proof_batch(
-                vec![blobs],
-                vec![commitments],
-                vec![proofs],
-                &kzg_settings,
-            );
-            match result {
-                Ok(result) => {
-                    assert_eq!(result, test.get_output().unwrap_or(false));
-                }
-                Err(_) => {
-                    assert!(test.get_output().is_none());
-                }
+            for (verify, kzg_settings) in [
+                (
+                    KzgProof::verify_kzg_proof as fn(_, _, _, _, _) -> _,
+                    &kzg_settings,
+                ),
+                (KzgProof::verify_kzg_proof, &built),
+                (KzgProof::verify_kzg_proof_fast, &built),
+            ] {
+                let before = ALLOCATIONS.with(|allocations| allocations.get());
+                let result = verify(&commitment, &z, &y, &proof, kzg_settings);
+                let after = ALLOCATIONS.with(|allocations| allocations.get());
+
+                assert_eq!(result.unwrap(), output);
+                assert_eq!(after, before);
             }
+            checked += 1;
         }
+        assert!(checked > 0);
+
+        // The hook does see allocations, e.g. the ones from preparing [tau]G2 on the spot
+        let test: Test<Input> = serde_yaml::from_str(VERIFY_KZG_PROOF_TESTS[0].1).unwrap();
+        let (commitment, z, y, proof) = (
+            test.input.get_commitment().unwrap(),
+            test.input.get_z().unwrap(),
+            test.input.get_y().unwrap(),
+            test.input.get_proof().unwrap(),
+        );
+        let before = ALLOCATIONS.with(|allocations| allocations.get());
+        let result = KzgProof::verify_kzg_proof(&commitment, &z, &y, &proof, &lean);
+        assert!(ALLOCATIONS.with(|allocations| allocations.get()) > before);
+        assert_eq!(result.unwrap(), test.get_output().unwrap());
     }
 
     #[test]
-    pub fn test_compute_challenge() {
-        let data = include_str!("../tests/verify_blob_kzg_proof/verify_blob_kzg_proof_case_correct_proof_fb324bc819407148/data.yaml");
+    pub fn test_verify_kzg_proof_fast() {
+        let kzg_settings = KzgSettings::load_trusted_setup_file().unwrap();
+        let lean = KzgSettings::builder()
+            .g1_lagrange(kzg_settings.g1_points)
+            .g2_monomial(kzg_settings.g2_points)
+            .precompute(false)
+            .build()
+            .unwrap();
 
-        let test: Test<BlobInput> = serde_yaml::from_str(data).unwrap();
-        let blob = test.input.get_blob().unwrap();
-        let commitment = safe_g1_affine_from_bytes(&test.input.get_commitment().unwrap()).unwrap();
+        let (_test_file, data) = VERIFY_KZG_PROOF_TESTS
+            .iter()
+            .find(|(test_file, _)| test_file.starts_with("verify_kzg_proof_case_correct_proof"))
+            .unwrap();
+        let test: Test<Input> = serde_yaml::from_str(data).unwrap();
+        let commitment = test.input.get_commitment().unwrap();
+        let z = test.input.get_z().unwrap();
+        let y = test.input.get_y().unwrap();
+        let proof = test.input.get_proof().unwrap();
 
-        let evaluation_challenge = compute_challenge(&blob, &commitment).unwrap();
+        assert!(
+            KzgProof::verify_kzg_proof_fast(&commitment, &z, &y, &proof, &kzg_settings).unwrap()
+        );
+        assert!(matches!(
+            KzgProof::verify_kzg_proof_fast(&commitment, &z, &y, &proof, &lean),
+            Err(KzgError::SetupNotPrecomputed)
+        ));
+        // The default entry point falls back to preparing [tau]G2 on every call
+        assert!(KzgProof::verify_kzg_proof(&commitment, &z, &y, &proof, &lean).unwrap());
+
+        let mut overwritten = kzg_settings.clone();
+        overwritten.g2_points = generate_insecure_setup(Scalar::from(7), 16).g2_points;
+        assert!(matches!(
+            KzgProof::verify_kzg_proof_fast(&commitment, &z, &y, &proof, &overwritten),
+            Err(KzgError::SetupNotPrecomputed)
+        ));
+    }
+
+    #[test]
+    pub fn test_pairing_check_replicates_verify_kzg_proof() {
+        let kzg_settings = KzgSettings::load_trusted_setup_file().unwrap();
 
+        let mut checked = 0;
+        for (_test_file, data) in VERIFY_KZG_PROOF_TESTS {
+            let test: Test<Input> = serde_yaml::from_str(data).unwrap();
+            let (Ok(commitment), Ok(z), Ok(y), Ok(proof), Some(output)) = (
+                test.input.get_commitment(),
+                test.input.get_z(),
+                test.input.get_y(),
+                test.input.get_proof(),
+                test.get_output(),
+            ) else {
+                continue;
+            };
+
+            // e(C - [y]G1, G2) == e(proof, [tau - z]G2)
+            let z = safe_scalar_affine_from_bytes(&z).unwrap();
+            let y = safe_scalar_affine_from_bytes(&y).unwrap();
+            let commitment = safe_g1_affine_from_bytes(&commitment).unwrap();
+            let proof = safe_g1_affine_from_bytes(&proof).unwrap();
+            let check = PairingCheck::<2>::new()
+                .add_term(
+                    (commitment - G1Affine::generator() * y).into(),
+                    G2Affine::generator(),
+                )
+                .add_term(
+                    -proof,
+                    (kzg_settings.g2_points[1] - G2Affine::generator() * z).into(),
+                );
+            assert_eq!(check.verify(), output);
+            checked += 1;
+        }
+        assert!(checked > 0);
+
+        assert!(PairingCheck::<0>::new().verify());
+        assert!(!PairingCheck::<2>::new()
+            .add_term(G1Affine::generator(), G2Affine::generator())
+            .verify());
+
+        // Prepared and unprepared G2 points make the same terms, and unused slots stay out
+        let g1 = G1Affine::from(G1Affine::generator() * Scalar::from(5));
+        assert!(PairingCheck::<4>::new()
+            .add_prepared_term(g1, prepared_g2_generator())
+            .add_term(-g1, G2Affine::generator())
+            .verify());
         assert_eq!(
-            format!("{evaluation_challenge}"),
-            "0x4f00eef944a21cb9f3ac3390702621e4bbf1198767c43c0fb9c8e9923bfbb31a"
+            PairingCheck::<4>::new()
+                .add_prepared_term(g1, prepared_g2_generator())
+                .miller_loop()
+                .final_exponentiation(),
+            PairingCheck::<1>::new()
+                .add_term(g1, G2Affine::generator())
+                .miller_loop()
+                .final_exponentiation()
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "at most 1 terms")]
+    pub fn test_pairing_check_capacity() {
+        let _ = PairingCheck::<1>::new()
+            .add_prepared_term(G1Affine::generator(), prepared_g2_generator())
+            .add_prepared_term(G1Affine::generator(), prepared_g2_generator());
+    }
+
+    #[test]
+    pub fn test_verify_kzg_proof_accumulate() {
+        use bls12_381::Gt;
+
+        let kzg_settings = KzgSettings::load_trusted_setup_file().unwrap();
+
+        let mut valid = Vec::new();
+        let mut checked = 0;
+        for (_test_file, data) in VERIFY_KZG_PROOF_TESTS.iter().take(32) {
+            let test: Test<Input> = serde_yaml::from_str(data).unwrap();
+            let (Ok(commitment), Ok(z), Ok(y), Ok(proof)) = (
+                test.input.get_commitment(),
+                test.input.get_z(),
+                test.input.get_y(),
+                test.input.get_proof(),
+            ) else {
+                continue;
+            };
+            let Ok(expected) =
+                KzgProof::verify_kzg_proof(&commitment, &z, &y, &proof, &kzg_settings)
+            else {
+                continue;
+            };
+
+            let mut acc = MillerLoopResult::default();
+            KzgProof::verify_kzg_proof_accumulate(
+                &commitment,
+                &z,
+                &y,
+                &proof,
+                &mut acc,
+                &kzg_settings,
+            )
+            .unwrap();
+            assert_eq!(acc.final_exponentiation() == Gt::identity(), expected);
+
+            if expected {
+                valid.push((commitment, z, y, proof));
+            }
+            checked += 1;
+        }
+        assert!(checked > 0);
+
+        // Valid proofs keep finalizing to the identity together, one invalid one breaks it
+        let mut acc = MillerLoopResult::default();
+        for (commitment, z, y, proof) in &valid {
+            KzgProof::verify_kzg_proof_accumulate(commitment, z, y, proof, &mut acc, &kzg_settings)
+                .unwrap();
+        }
+        assert!(valid.len() > 1);
+        assert_eq!(acc.final_exponentiation(), Gt::identity());
+
+        let (commitment, z, _, proof) = &valid[0];
+        let wrong_y = scalar_to_blob_bytes(&Scalar::from(7), Endianness::Big);
+        KzgProof::verify_kzg_proof_accumulate(
+            commitment,
+            z,
+            &wrong_y,
+            proof,
+            &mut acc,
+            &kzg_settings,
         )
+        .unwrap();
+        assert_ne!(acc.final_exponentiation(), Gt::identity());
+    }
+
+    #[test]
+    #[ignore = "benchmark, run with `cargo test --release -- --ignored --nocapture`"]
+    pub fn bench_verify_kzg_proof_vs_two_pairings() {
+        use bls12_381::pairing;
+        use std::time::Instant;
+
+        const ITERATIONS: u32 = 20;
+
+        let data = include_str!("../tests/verify_kzg_proof/verify_kzg_proof_case_correct_proof_02e696ada7d4631d/data.yaml");
+        let test: Test<Input> = serde_yaml::from_str(data).unwrap();
+        let kzg_settings = KzgSettings::load_trusted_setup_file().unwrap();
+        let (commitment, z, y, proof) = (
+            test.input.get_commitment().unwrap(),
+            test.input.get_z().unwrap(),
+            test.input.get_y().unwrap(),
+            test.input.get_proof().unwrap(),
+        );
+
+        // The same check with a final exponentiation per pairing
+        let start = Instant::now();
+        for _ in 0..ITERATIONS {
+            let z = safe_scalar_affine_from_bytes(&z).unwrap();
+            let y = safe_scalar_affine_from_bytes(&y).unwrap();
+            let commitment = safe_g1_affine_from_bytes(&commitment).unwrap();
+            let proof = safe_g1_affine_from_bytes(&proof).unwrap();
+            let x_minus_z = G2Affine::from(kzg_settings.g2_points[1] - G2Affine::generator() * z);
+            let p_minus_y = G1Affine::from(commitment - G1Affine::generator() * y);
+            assert_eq!(
+                pairing(&p_minus_y, &G2Affine::generator()),
+                pairing(&proof, &x_minus_z)
+            );
+        }
+        let separate = start.elapsed() / ITERATIONS;
+
+        let start = Instant::now();
+        for _ in 0..ITERATIONS {
+            assert!(
+                KzgProof::verify_kzg_proof(&commitment, &z, &y, &proof, &kzg_settings).unwrap()
+            );
+        }
+        let combined = start.elapsed() / ITERATIONS;
+
+        println!("two pairings: {separate:?}, verify_kzg_proof: {combined:?}");
+        assert!(combined < separate);
+    }
+
+    #[test]
+    pub fn test_verify_kzg_proof_rejects_non_canonical_z() {
+        let kzg_settings = KzgSettings::load_trusted_setup_file().unwrap();
+
+        let mut checked = 0;
+        for (test_file, data) in VERIFY_KZG_PROOF_TESTS {
+            if !test_file.contains("invalid_z") {
+                continue;
+            }
+            let test: Test<Input> = serde_yaml::from_str(data).unwrap();
+            // Wrongly sized z values are rejected before reaching the field element parser
+            let (Ok(commitment), Ok(z), Ok(y), Ok(proof)) = (
+                test.input.get_commitment(),
+                test.input.get_z(),
+                test.input.get_y(),
+                test.input.get_proof(),
+            ) else {
+                continue;
+            };
+
+            let result = KzgProof::verify_kzg_proof(&commitment, &z, &y, &proof, &kzg_settings);
+            assert!(matches!(result, Err(KzgError::BadFieldElement(_))));
+
+            // The same value as y is rejected too, before any pairing
+            let result = KzgProof::verify_kzg_proof(&commitment, &y, &z, &proof, &kzg_settings);
+            assert!(matches!(result, Err(KzgError::BadFieldElement(_))));
+            checked += 1;
+        }
+        assert!(checked > 0);
+    }
+
+    #[derive(Debug, Deserialize)]
+    pub struct BlobInput<'a> {
+        blob: &'a str,
+        commitment: &'a str,
+        proof: &'a str,
+    }
+
+    impl BlobInput<'_> {
+        pub fn get_blob(&self) -> Result<Blob, KzgError> {
+            Blob::from_hex(self.blob)
+        }
+
+        pub fn get_commitment(&self) -> Result<Bytes48, KzgError> {
+            Bytes48::from_hex(self.commitment)
+        }
+
+        pub fn get_proof(&self) -> Result<Bytes48, KzgError> {
+            Bytes48::from_hex(self.proof)
+        }
+    }
+
+    /// The first `n` entries of the `verify_blob_kzg_proof` vectors that hold a valid proof
+    pub fn valid_blob_vectors(n: usize) -> (Vec<Blob>, Vec<Bytes48>, Vec<Bytes48>) {
+        let mut blobs = Vec::new();
+        let mut commitments = Vec::new();
+        let mut proofs = Vec::new();
+        for (_test_file, data) in VERIFY_BLOB_KZG_PROOF_TESTS {
+            if blobs.len() == n {
+                break;
+            }
+            let test: Test<BlobInput> = serde_yaml::from_str(data).unwrap();
+            let (Ok(blob), Ok(commitment), Ok(proof), Some(true)) = (
+                test.input.get_blob(),
+                test.input.get_commitment(),
+                test.input.get_proof(),
+                test.get_output(),
+            ) else {
+                continue;
+            };
+            blobs.push(blob);
+            commitments.push(commitment);
+            proofs.push(proof);
+        }
+        assert_eq!(blobs.len(), n, "not enough valid vectors");
+        (blobs, commitments, proofs)
+    }
+
+    #[test]
+    pub fn test_verify_blob_kzg_proof() {
+        let kzg_settings = KzgSettings::load_trusted_setup_file().unwrap();
+        let test_files = VERIFY_BLOB_KZG_PROOF_TESTS;
+
+        for (_test_file, data) in test_files {
+            let test: Test<BlobInput> = serde_yaml::from_str(data).unwrap();
+            let (Ok(blob), Ok(commitment), Ok(proof)) = (
+                test.input.get_blob(),
+                test.input.get_commitment(),
+                test.input.get_proof(),
+            ) else {
+                assert!(test.get_output().is_none());
+                continue;
+            };
+
+            let result = KzgProof::verify_blob_kzg_proof(blob, &commitment, &proof, &kzg_settings);
+            match result {
+                Ok(result) => {
+                    assert_eq!(result, test.get_output().unwrap_or(false));
+                }
+                Err(_) => {
+                    assert!(test.get_output().is_none());
+                }
+            }
+        }
+    }
+
+    #[test]
+    pub fn test_verify_blob_kzg_proof_ct() {
+        let kzg_settings = KzgSettings::load_trusted_setup_file().unwrap();
+        let test_files = VERIFY_BLOB_KZG_PROOF_TESTS;
+
+        for (_test_file, data) in test_files {
+            let test: Test<BlobInput> = serde_yaml::from_str(data).unwrap();
+            let (Ok(blob), Ok(commitment), Ok(proof)) = (
+                test.input.get_blob(),
+                test.input.get_commitment(),
+                test.input.get_proof(),
+            ) else {
+                continue;
+            };
+
+            let expected =
+                KzgProof::verify_blob_kzg_proof(blob.clone(), &commitment, &proof, &kzg_settings);
+            let result =
+                KzgProof::verify_blob_kzg_proof_ct(blob, &commitment, &proof, &kzg_settings);
+            match (result, expected) {
+                (Ok(result), Ok(expected)) => assert_eq!(bool::from(result), expected),
+                (Err(_), Err(_)) => {}
+                _ => panic!("constant-time and standard verification disagree"),
+            }
+        }
+    }
+
+    #[test]
+    pub fn test_verify_blob_kzg_proof_with_transcript() {
+        let kzg_settings = KzgSettings::load_trusted_setup_file().unwrap();
+
+        let mut checked = 0;
+        for (_test_file, data) in VERIFY_BLOB_KZG_PROOF_TESTS.iter().take(8) {
+            let test: Test<BlobInput> = serde_yaml::from_str(data).unwrap();
+            let (Ok(blob), Ok(commitment), Ok(proof), Some(output)) = (
+                test.input.get_blob(),
+                test.input.get_commitment(),
+                test.input.get_proof(),
+                test.get_output(),
+            ) else {
+                continue;
+            };
+
+            let Ok((valid, z)) = KzgProof::verify_blob_kzg_proof_with_transcript(
+                blob.clone(),
+                &commitment,
+                &proof,
+                &kzg_settings,
+            ) else {
+                assert!(!output);
+                continue;
+            };
+            assert_eq!(valid, output);
+            let commitment = safe_g1_affine_from_bytes(&commitment).unwrap();
+            assert_eq!(z, compute_challenge(&blob, &commitment).unwrap());
+            checked += 1;
+        }
+        assert!(checked > 0);
+    }
+
+    #[test]
+    pub fn test_verify_blob_kzg_proof_strict() {
+        let kzg_settings = KzgSettings::load_trusted_setup_file().unwrap();
+
+        let mut cases = Vec::new();
+        for (_test_file, data) in VERIFY_BLOB_KZG_PROOF_TESTS {
+            let test: Test<BlobInput> = serde_yaml::from_str(data).unwrap();
+            if test.get_output() == Some(true) && cases.len() < 2 {
+                cases.push(test);
+            }
+        }
+        let (a, b) = (&cases[0].input, &cases[1].input);
+
+        let blob = a.get_blob().unwrap();
+        let commitment = a.get_commitment().unwrap();
+        assert_eq!(
+            poly_to_kzg_commitment(&blob.as_polynomial().unwrap(), &kzg_settings)
+                .unwrap()
+                .to_compressed(),
+            <[u8; 48]>::from(commitment.clone())
+        );
+        assert!(KzgProof::verify_blob_kzg_proof_strict(
+            blob.clone(),
+            &commitment,
+            &a.get_proof().unwrap(),
+            &kzg_settings,
+        )
+        .unwrap());
+
+        // A valid (commitment, proof) pair attached to a different blob
+        let substituted = KzgProof::verify_blob_kzg_proof_strict(
+            blob,
+            &b.get_commitment().unwrap(),
+            &b.get_proof().unwrap(),
+            &kzg_settings,
+        );
+        assert!(matches!(substituted, Err(KzgError::BadArgs(_))));
+    }
+
+    #[derive(Debug, Deserialize)]
+    struct BlobBatchInput<'a> {
+        #[serde(borrow)]
+        blobs: Vec<&'a str>,
+        #[serde(borrow)]
+        commitments: Vec<&'a str>,
+        #[serde(borrow)]
+        proofs: Vec<&'a str>,
+    }
+
+    impl<'a> BlobBatchInput<'a> {
+        pub fn get_blobs(&self) -> Result<Vec<Blob>, KzgError> {
+            // A plain loop keeps fewer 128 KiB blobs on the stack than `collect` in debug builds
+            let mut blobs = Vec::with_capacity(self.blobs.len());
+            for blob in &self.blobs {
+                blobs.push(Blob::from_hex(blob)?);
+            }
+            Ok(blobs)
+        }
+
+        pub fn get_commitments(&self) -> Result<Vec<Bytes48>, KzgError> {
+            self.commitments
+                .iter()
+                .map(|commitment| Bytes48::from_hex(commitment))
+                .collect()
+        }
+
+        pub fn get_proofs(&self) -> Result<Vec<Bytes48>, KzgError> {
+            self.proofs
+                .iter()
+                .map(|proof| Bytes48::from_hex(proof))
+                .collect()
+        }
+    }
+
+    #[test]
+    pub fn test_verify_blob_kzg_proof_batch() {
+        let test_files = VERIFY_BLOB_KZG_PROOF_BATCH_TESTS;
+        let kzg_settings = KzgSettings::load_trusted_setup_file().unwrap();
+
+        let mut sizes = Vec::new();
+        for (_test_file, data) in test_files {
+            let test: Test<BlobBatchInput> = serde_yaml::from_str(data).unwrap();
+            let (Ok(blobs), Ok(commitments), Ok(proofs)) = (
+                test.input.get_blobs(),
+                test.input.get_commitments(),
+                test.input.get_proofs(),
+            ) else {
+                assert!(test.get_output().is_none());
+                continue;
+            };
+            sizes.push(blobs.len());
+
+            let result =
+                KzgProof::verify_blob_kzg_proof_batch(blobs, commitments, proofs, &kzg_settings);
+            match result {
+                Ok(result) => {
+                    assert_eq!(result, test.get_output().unwrap_or(false));
+                }
+                Err(_) => {
+                    assert!(test.get_output().is_none());
+                }
+            }
+        }
+        // The vectors cover the empty and single-entry batches as well as larger ones
+        assert!(sizes.contains(&0));
+        assert!(sizes.contains(&1));
+        assert!(sizes.iter().any(|&size| size > 1));
+    }
+
+    #[test]
+    pub fn test_verify_blob_kzg_proof_batch_single_entry() {
+        let kzg_settings = KzgSettings::load_trusted_setup_file().unwrap();
+        assert!(
+            KzgProof::verify_blob_kzg_proof_batch(vec![], vec![], vec![], &kzg_settings).unwrap()
+        );
+
+        assert!(matches!(
+            KzgProof::verify_blob_kzg_proof_batch_nonempty(vec![], vec![], vec![], &kzg_settings),
+            Err(KzgError::Empty)
+        ));
+
+        // A batch of one gives the same answer as verifying the blob on its own
+        let mut checked = 0;
+        for (_test_file, data) in VERIFY_BLOB_KZG_PROOF_TESTS {
+            let test: Test<BlobInput> = serde_yaml::from_str(data).unwrap();
+            let (Ok(blob), Ok(commitment), Ok(proof)) = (
+                test.input.get_blob(),
+                test.input.get_commitment(),
+                test.input.get_proof(),
+            ) else {
+                continue;
+            };
+
+            let single =
+                KzgProof::verify_blob_kzg_proof(blob.clone(), &commitment, &proof, &kzg_settings);
+            let batch = KzgProof::verify_blob_kzg_proof_batch(
+                vec![blob.clone()],
+                vec![commitment.clone()],
+                vec![proof.clone()],
+                &kzg_settings,
+            );
+            let nonempty = KzgProof::verify_blob_kzg_proof_batch_nonempty(
+                vec![blob],
+                vec![commitment],
+                vec![proof],
+                &kzg_settings,
+            );
+            match (single, batch, nonempty) {
+                (Ok(single), Ok(batch), Ok(nonempty)) => {
+                    assert_eq!(single, batch);
+                    assert_eq!(single, nonempty);
+                }
+                (single, batch, nonempty) => {
+                    assert!(single.is_err() && batch.is_err() && nonempty.is_err())
+                }
+            }
+            checked += 1;
+        }
+        assert!(checked > 0);
+    }
+
+    #[test]
+    pub fn test_verify_blob_kzg_proof_batch_masked() {
+        let kzg_settings = KzgSettings::load_trusted_setup_file().unwrap();
+
+        // Three valid entries followed by one with an incorrect proof
+        let mut blobs = Vec::new();
+        let mut commitments = Vec::new();
+        let mut proofs = Vec::new();
+        for wanted in [true, false] {
+            for (_test_file, data) in VERIFY_BLOB_KZG_PROOF_TESTS {
+                let test: Test<BlobInput> = serde_yaml::from_str(data).unwrap();
+                let (Ok(blob), Ok(commitment), Ok(proof)) = (
+                    test.input.get_blob(),
+                    test.input.get_commitment(),
+                    test.input.get_proof(),
+                ) else {
+                    continue;
+                };
+                if test.get_output() != Some(wanted) {
+                    continue;
+                }
+                blobs.push(blob);
+                commitments.push(commitment);
+                proofs.push(proof);
+                if blobs.len() == 3 || !wanted {
+                    break;
+                }
+            }
+        }
+        assert_eq!(blobs.len(), 4);
+
+        for mask in [
+            [true, true, true, false],
+            [true, false, true, false],
+            [false, true, false, true],
+            [true, true, true, true],
+            [false, false, false, false],
+        ] {
+            let masked = KzgProof::verify_blob_kzg_proof_batch_masked(
+                blobs.clone(),
+                commitments.clone(),
+                proofs.clone(),
+                &mask,
+                &kzg_settings,
+            )
+            .unwrap();
+
+            let selected = (0..mask.len()).filter(|&i| mask[i]).collect::<Vec<_>>();
+            let filtered = KzgProof::verify_blob_kzg_proof_batch(
+                selected.iter().map(|&i| blobs[i].clone()).collect(),
+                selected.iter().map(|&i| commitments[i].clone()).collect(),
+                selected.iter().map(|&i| proofs[i].clone()).collect(),
+                &kzg_settings,
+            )
+            .unwrap();
+
+            assert_eq!(masked, filtered);
+            assert_eq!(masked, !mask[3]);
+        }
+
+        assert!(KzgProof::verify_blob_kzg_proof_batch_masked(
+            blobs,
+            commitments,
+            proofs,
+            &[true, true],
+            &kzg_settings,
+        )
+        .is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "op-counts")]
+    pub fn test_verify_kzg_proof_op_counts() {
+        use crate::op_counts::measure;
+
+        let kzg_settings = KzgSettings::load_trusted_setup_file().unwrap();
+        let (_test_file, data) = VERIFY_KZG_PROOF_TESTS
+            .iter()
+            .find(|(test_file, _)| test_file.contains("_correct_proof_"))
+            .unwrap();
+        let test: Test<Input> = serde_yaml::from_str(data).unwrap();
+        let commitment = test.input.get_commitment().unwrap();
+        let z = test.input.get_z().unwrap();
+        let y = test.input.get_y().unwrap();
+        let proof = test.input.get_proof().unwrap();
+
+        // Exactly one multi-Miller loop, whether the proof is accepted or rejected
+        let wrong_y = scalar_to_blob_bytes(&Scalar::from(7), Endianness::Big);
+        for (y, expected) in [(&y, true), (&wrong_y, false)] {
+            let (result, counts) =
+                measure(|| KzgProof::verify_kzg_proof(&commitment, &z, y, &proof, &kzg_settings));
+            assert_eq!(result.unwrap(), expected);
+            assert_eq!(counts.multi_miller_loops, 1);
+            assert_eq!(counts.final_exponentiations, 1);
+            assert_eq!(counts.pairings, 0);
+            assert_eq!(counts.g1_scalar_muls, 2);
+            assert_eq!(counts.field_inversions, 0);
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "futures")]
+    pub fn test_verify_blob_stream() {
+        use futures::{executor::block_on, stream};
+
+        let kzg_settings = KzgSettings::load_trusted_setup_file().unwrap();
+
+        // Every well-formed entry, the last one carrying an incorrect proof
+        let mut blobs = Vec::new();
+        let mut commitments = Vec::new();
+        let mut proofs = Vec::new();
+        let mut outputs = Vec::new();
+        for (_test_file, data) in VERIFY_BLOB_KZG_PROOF_TESTS {
+            let test: Test<BlobInput> = serde_yaml::from_str(data).unwrap();
+            let (Ok(blob), Ok(commitment), Ok(proof), Some(output)) = (
+                test.input.get_blob(),
+                test.input.get_commitment(),
+                test.input.get_proof(),
+                test.get_output(),
+            ) else {
+                continue;
+            };
+            if outputs.len() == 3 && output {
+                continue;
+            }
+            blobs.push(blob);
+            commitments.push(commitment);
+            proofs.push(proof);
+            outputs.push(output);
+            if outputs.len() == 4 {
+                break;
+            }
+        }
+        assert_eq!(outputs, [true, true, true, false]);
+
+        for len in 0..=blobs.len() {
+            let items = (0..len)
+                .map(|i| (blobs[i].clone(), commitments[i].clone(), proofs[i].clone()))
+                .collect::<Vec<_>>();
+            let streamed = block_on(KzgProof::verify_blob_stream(
+                stream::iter(items),
+                &kzg_settings,
+            ))
+            .unwrap();
+
+            let batched = KzgProof::verify_blob_kzg_proof_batch(
+                blobs[..len].to_vec(),
+                commitments[..len].to_vec(),
+                proofs[..len].to_vec(),
+                &kzg_settings,
+            )
+            .unwrap();
+            assert_eq!(streamed, batched);
+            assert_eq!(streamed, len < 4);
+        }
+    }
+
+    #[test]
+    pub fn test_verify_blob_kzg_proof_batch_identity_padding() {
+        let kzg_settings = KzgSettings::load_trusted_setup_file().unwrap();
+
+        let (blobs, commitments, mut proofs) = valid_blob_vectors(2);
+
+        let identity = Bytes48::from(KzgCommitment::identity());
+        let pad =
+            |mut blobs: Vec<Blob>, mut commitments: Vec<Bytes48>, mut proofs: Vec<Bytes48>| {
+                for _ in 0..2 {
+                    blobs.push(Blob::zero());
+                    commitments.push(identity.clone());
+                    proofs.push(identity.clone());
+                }
+                KzgProof::verify_blob_kzg_proof_batch(blobs, commitments, proofs, &kzg_settings)
+                    .unwrap()
+            };
+
+        assert!(pad(blobs.clone(), commitments.clone(), proofs.clone()));
+        assert!(pad(vec![], vec![], vec![]));
+
+        // Padding does not mask a failing entry
+        proofs.swap(0, 1);
+        assert!(!pad(blobs, commitments, proofs));
+    }
+
+    #[test]
+    pub fn test_verify_blob_kzg_proof_batch_mismatched_lengths() {
+        let kzg_settings = KzgSettings::load_trusted_setup_file().unwrap();
+        let bytes48 = Bytes48::from([0u8; 48]);
+
+        // A single blob used to index the commitments and proofs before checking their lengths
+        for (commitments, proofs) in [(0, 0), (0, 1), (1, 0), (2, 1)] {
+            assert!(KzgProof::verify_blob_kzg_proof_batch(
+                vec![Blob::zero()],
+                vec![bytes48.clone(); commitments],
+                vec![bytes48.clone(); proofs],
+                &kzg_settings,
+            )
+            .is_err());
+        }
+        assert!(KzgProof::verify_blob_kzg_proof_batch(
+            vec![],
+            vec![bytes48.clone()],
+            vec![],
+            &kzg_settings,
+        )
+        .is_err());
+
+        // Checked before any point is decoded, so the all-zero encodings don't matter
+        assert!(matches!(
+            KzgProof::verify_blob_kzg_proof_batch(
+                vec![Blob::zero(); 3],
+                vec![bytes48.clone(); 2],
+                vec![bytes48.clone(); 3],
+                &kzg_settings,
+            ),
+            Err(KzgError::MismatchedLengths {
+                expected: 3,
+                got: 2
+            })
+        ));
+    }
+
+    #[test]
+    pub fn test_batch_preflight() {
+        let kzg_settings = KzgSettings::load_trusted_setup_file().unwrap();
+        let point = |i: u64| -> Bytes48 {
+            G1Affine::from(G1Affine::generator() * Scalar::from(i))
+                .to_compressed()
+                .into()
+        };
+        let blobs = vec![Blob::zero(), Blob::zero()];
+        let commitments = vec![point(1), point(2)];
+        let proofs = vec![point(3), point(4)];
+        batch_preflight(&blobs, &commitments, &proofs, &kzg_settings).unwrap();
+        batch_preflight(&[], &[], &[], &kzg_settings).unwrap();
+
+        assert!(matches!(
+            batch_preflight(&blobs, &commitments[..1], &proofs, &kzg_settings),
+            Err(KzgError::MismatchedLengths {
+                expected: 2,
+                got: 1
+            })
+        ));
+        assert!(matches!(
+            batch_preflight(&blobs, &commitments, &proofs[..1], &kzg_settings),
+            Err(KzgError::MismatchedLengths {
+                expected: 2,
+                got: 1
+            })
+        ));
+
+        let mut not_on_curve = [0u8; 48];
+        not_on_curve[0] = 0x80;
+        not_on_curve[47] = 1;
+        let mut bad_commitments = commitments.clone();
+        bad_commitments[1] = not_on_curve.into();
+        let err = batch_preflight(&blobs, &bad_commitments, &proofs, &kzg_settings).unwrap_err();
+        assert!(
+            matches!(&err, KzgError::NotOnCurve(message) if message.starts_with("Commitment 1: "))
+        );
+        let mut bad_proofs = proofs.clone();
+        bad_proofs[0] = not_on_curve.into();
+        let err = batch_preflight(&blobs, &commitments, &bad_proofs, &kzg_settings).unwrap_err();
+        assert!(matches!(&err, KzgError::NotOnCurve(message) if message.starts_with("Proof 0: ")));
+
+        let mut field_elements = Blob::zero().to_field_element_bytes();
+        field_elements[5] = Bytes32::from([0xff; 32]);
+        let bad_blobs = vec![
+            Blob::zero(),
+            Blob::from_field_element_bytes(&field_elements).unwrap(),
+        ];
+        let err = batch_preflight(&bad_blobs, &commitments, &proofs, &kzg_settings).unwrap_err();
+        assert!(matches!(
+            &err,
+            KzgError::BadFieldElement(message) if message == "Field element 5 of blob 1 is not canonical"
+        ));
+
+        // The same commitment with different proofs is fine, the same pair twice is not
+        batch_preflight(&blobs, &[point(1), point(1)], &proofs, &kzg_settings).unwrap();
+        let err = batch_preflight(
+            &blobs,
+            &[point(1), point(1)],
+            &[point(3), point(3)],
+            &kzg_settings,
+        )
+        .unwrap_err();
+        assert!(matches!(
+            &err,
+            KzgError::BadArgs(message) if message == "Entries 0 and 1 have the same commitment and proof"
+        ));
+    }
+
+    #[test]
+    pub fn test_verify_blob_kzg_proof_batch_verbose() {
+        let kzg_settings = KzgSettings::load_trusted_setup_file().unwrap();
+
+        let (blobs, commitments, proofs) = valid_blob_vectors(2);
+
+        let (valid, stats) = KzgProof::verify_blob_kzg_proof_batch_verbose(
+            blobs.clone(),
+            commitments.clone(),
+            proofs.clone(),
+            &kzg_settings,
+        )
+        .unwrap();
+        assert!(valid);
+        assert_eq!(
+            stats,
+            VerifyStats {
+                used_precompute: true,
+                used_rayon: cfg!(feature = "parallel"),
+                pairings: 2,
+            }
+        );
+
+        // The lone entry of a batch of one is verified inline
+        let (valid, stats) = KzgProof::verify_blob_kzg_proof_batch_verbose(
+            blobs[..1].to_vec(),
+            commitments[..1].to_vec(),
+            proofs[..1].to_vec(),
+            &kzg_settings,
+        )
+        .unwrap();
+        assert!(valid);
+        assert_eq!(
+            stats,
+            VerifyStats {
+                used_precompute: true,
+                used_rayon: false,
+                pairings: 2,
+            }
+        );
+
+        // So is [tau]G2 when the settings were built without precomputing it
+        let unprepared = KzgSettings::builder()
+            .g1_lagrange(kzg_settings.g1_points)
+            .g2_monomial(kzg_settings.g2_points)
+            .precompute(false)
+            .build()
+            .unwrap();
+        let (valid, stats) = KzgProof::verify_blob_kzg_proof_batch_verbose(
+            blobs.clone(),
+            commitments.clone(),
+            proofs.clone(),
+            &unprepared,
+        )
+        .unwrap();
+        assert!(valid);
+        assert!(!stats.used_precompute);
+
+        // G2 points written over the field after the settings were built are prepared on the spot
+        let mut overwritten = kzg_settings.clone();
+        overwritten.g2_points = generate_insecure_setup(Scalar::from(7), 16).g2_points;
+        let (valid, stats) =
+            KzgProof::verify_blob_kzg_proof_batch_verbose(blobs, commitments, proofs, &overwritten)
+                .unwrap();
+        assert!(!valid);
+        assert!(!stats.used_precompute);
+
+        let (valid, stats) =
+            KzgProof::verify_blob_kzg_proof_batch_verbose(vec![], vec![], vec![], &kzg_settings)
+                .unwrap();
+        assert!(valid);
+        assert_eq!(stats.pairings, 0);
+        assert!(!stats.used_rayon);
+    }
+
+    #[test]
+    #[cfg(feature = "parallel")]
+    pub fn test_verify_blob_kzg_proof_batch_chunked() {
+        let kzg_settings = KzgSettings::load_trusted_setup_file().unwrap();
+
+        let (blobs, commitments, proofs) = valid_blob_vectors(5);
+        let n = blobs.len();
+
+        let mut swapped_proofs = proofs.clone();
+        swapped_proofs.swap(1, 3);
+        for proofs in [proofs, swapped_proofs] {
+            let expected = KzgProof::verify_blob_kzg_proof_batch(
+                blobs.clone(),
+                commitments.clone(),
+                proofs.clone(),
+                &kzg_settings,
+            )
+            .unwrap();
+            for chunk_size in [1, 2, 3, n, n + 1] {
+                let chunked = KzgProof::verify_blob_kzg_proof_batch_chunked(
+                    blobs.clone(),
+                    commitments.clone(),
+                    proofs.clone(),
+                    chunk_size,
+                    &kzg_settings,
+                )
+                .unwrap();
+                assert_eq!(chunked, expected, "chunk size {}", chunk_size);
+            }
+        }
+
+        assert!(KzgProof::verify_blob_kzg_proof_batch_chunked(
+            blobs,
+            commitments,
+            vec![],
+            1,
+            &kzg_settings
+        )
+        .is_err());
+        assert!(KzgProof::verify_blob_kzg_proof_batch_chunked(
+            vec![],
+            vec![],
+            vec![],
+            0,
+            &kzg_settings
+        )
+        .is_err());
+    }
+
+    #[test]
+    pub fn test_verify_prepared_batch() {
+        let kzg_settings = KzgSettings::load_trusted_setup_file().unwrap();
+
+        let (blobs, commitments, mut proofs) = valid_blob_vectors(3);
+
+        let prepared = blobs
+            .iter()
+            .zip(&commitments)
+            .map(|(blob, commitment)| PreparedBlob::new(blob, commitment, &kzg_settings).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            prepared[0].polynomial(),
+            blobs[0].as_polynomial().unwrap().as_slice()
+        );
+
+        // Overlapping batches reuse the same prepared blobs
+        for range in [0..3, 1..3, 0..1, 0..0] {
+            let expected = KzgProof::verify_blob_kzg_proof_batch(
+                blobs[range.clone()].to_vec(),
+                commitments[range.clone()].to_vec(),
+                proofs[range.clone()].to_vec(),
+                &kzg_settings,
+            )
+            .unwrap();
+            let result = KzgProof::verify_prepared_batch(
+                &prepared[range.clone()],
+                &proofs[range],
+                &kzg_settings,
+            )
+            .unwrap();
+            assert!(result);
+            assert_eq!(result, expected);
+        }
+
+        proofs.swap(0, 2);
+        assert!(!KzgProof::verify_prepared_batch(&prepared, &proofs, &kzg_settings).unwrap());
+        assert!(KzgProof::verify_prepared_batch(&prepared, &proofs[1..], &kzg_settings).is_err());
+    }
+
+    #[test]
+    pub fn test_verify_blob_kzg_proof_batch_owned() {
+        let kzg_settings = KzgSettings::load_trusted_setup_file().unwrap();
+
+        let (blobs, commitments, mut proofs) = valid_blob_vectors(3);
+
+        for len in 0..=blobs.len() {
+            let owned = KzgProof::verify_blob_kzg_proof_batch_owned(
+                blobs[..len].to_vec(),
+                commitments[..len].to_vec(),
+                proofs[..len].to_vec(),
+                &kzg_settings,
+            )
+            .unwrap();
+            let borrowed = KzgProof::verify_blob_kzg_proof_batch(
+                blobs[..len].to_vec(),
+                commitments[..len].to_vec(),
+                proofs[..len].to_vec(),
+                &kzg_settings,
+            )
+            .unwrap();
+            assert!(owned);
+            assert_eq!(owned, borrowed);
+        }
+
+        // Swapping two proofs breaks both
+        proofs.swap(0, 1);
+        assert!(!KzgProof::verify_blob_kzg_proof_batch_owned(
+            blobs.clone(),
+            commitments.clone(),
+            proofs.clone(),
+            &kzg_settings
+        )
+        .unwrap());
+        assert!(!KzgProof::verify_blob_kzg_proof_batch(
+            blobs.clone(),
+            commitments.clone(),
+            proofs.clone(),
+            &kzg_settings
+        )
+        .unwrap());
+
+        assert!(KzgProof::verify_blob_kzg_proof_batch_owned(
+            blobs,
+            commitments,
+            proofs[1..].to_vec(),
+            &kzg_settings
+        )
+        .is_err());
+    }
+
+    #[test]
+    pub fn test_compute_batch_challenge_is_deterministic() {
+        let kzg_settings = KzgSettings::load_trusted_setup_file().unwrap();
+
+        let commitments = [G1Affine::generator(), G1Affine::identity()];
+        let zs = [Scalar::from(1), Scalar::from(2)];
+        let ys = [Scalar::from(3), -Scalar::one()];
+        let proofs = [
+            G1Affine::from(G1Affine::generator() * Scalar::from(4)),
+            G1Affine::generator(),
+        ];
+
+        let r = compute_batch_challenge(&commitments, &zs, &ys, &proofs, &kzg_settings).unwrap();
+        for _ in 0..3 {
+            assert_eq!(
+                compute_batch_challenge(&commitments, &zs, &ys, &proofs, &kzg_settings).unwrap(),
+                r
+            );
+        }
+
+        // The c-kzg transcript, assembled by hand
+        let mut transcript = Vec::new();
+        transcript.extend_from_slice(RANDOM_CHALLENGE_KZG_BATCH_DOMAIN.as_bytes());
+        transcript.extend_from_slice(&4096_u64.to_be_bytes());
+        transcript.extend_from_slice(&2_u64.to_be_bytes());
+        for i in 0..2 {
+            transcript.extend_from_slice(&commitments[i].to_compressed());
+            transcript.extend(zs[i].to_bytes().iter().rev());
+            transcript.extend(ys[i].to_bytes().iter().rev());
+            transcript.extend_from_slice(&proofs[i].to_compressed());
+        }
+        assert_eq!(
+            r,
+            scalar_from_bytes_unchecked(Sha256::digest(&transcript).into())
+        );
+
+        let r_powers = compute_r_powers(&commitments, &zs, &ys, &proofs, &kzg_settings).unwrap();
+        assert_eq!(r_powers, [Scalar::one(), r]);
+
+        // Every input is bound
+        let other_ys = [ys[0], ys[0]];
+        assert_ne!(
+            compute_batch_challenge(&commitments, &zs, &other_ys, &proofs, &kzg_settings).unwrap(),
+            r
+        );
+        assert!(
+            compute_batch_challenge(&commitments, &zs[..1], &ys, &proofs, &kzg_settings).is_err()
+        );
+    }
+
+    #[test]
+    pub fn test_g1_msm_matches_scalar_muls() {
+        let points = (1..=37u64)
+            .map(|i| G1Projective::generator() * Scalar::from(i))
+            .collect::<Vec<_>>();
+        let scalars = (0..37u64)
+            .map(|i| Scalar::from(i * i + 3))
+            .collect::<Vec<_>>();
+
+        let expected = points
+            .iter()
+            .zip(&scalars)
+            .fold(G1Projective::identity(), |acc, (p, s)| acc + p * s);
+        assert_eq!(g1_msm(&points, &scalars), expected);
+        assert_eq!(g1_msm(&[], &[]), G1Projective::identity());
+    }
+
+    #[test]
+    pub fn test_verify_blob_kzg_proof_batch_with_aggregates() {
+        let kzg_settings = KzgSettings::load_trusted_setup_file().unwrap();
+
+        let (blobs, commitments, proofs) = valid_blob_vectors(3);
+        let commitments = commitments
+            .iter()
+            .map(|commitment| safe_g1_affine_from_bytes(commitment).unwrap())
+            .collect::<Vec<_>>();
+        let proofs = proofs
+            .iter()
+            .map(|proof| safe_g1_affine_from_bytes(proof).unwrap())
+            .collect::<Vec<_>>();
+
+        let expected = KzgProof::verify_blob_kzg_proof_batch(
+            blobs.clone(),
+            commitments
+                .iter()
+                .map(|c| c.to_compressed().into())
+                .collect(),
+            proofs.iter().map(|p| p.to_compressed().into()).collect(),
+            &kzg_settings,
+        )
+        .unwrap();
+        assert!(expected);
+
+        let (zs, ys) =
+            compute_challenges_and_evaluate_polynomial(blobs, &commitments, &kzg_settings).unwrap();
+        let (aggregated_proof, aggregated_commitment_minus_eval) =
+            compute_batch_aggregates(&commitments, &zs, &ys, &proofs, &kzg_settings).unwrap();
+        assert_eq!(
+            KzgProof::verify_blob_kzg_proof_batch_with_aggregates(
+                aggregated_proof,
+                aggregated_commitment_minus_eval,
+                &kzg_settings
+            ),
+            expected
+        );
+
+        // Aggregates for the wrong evaluations fail
+        let wrong_ys = ys.iter().map(|y| y + Scalar::one()).collect::<Vec<_>>();
+        let (aggregated_proof, aggregated_commitment_minus_eval) =
+            compute_batch_aggregates(&commitments, &zs, &wrong_ys, &proofs, &kzg_settings).unwrap();
+        assert!(!KzgProof::verify_blob_kzg_proof_batch_with_aggregates(
+            aggregated_proof,
+            aggregated_commitment_minus_eval,
+            &kzg_settings
+        ));
+    }
+
+    #[test]
+    pub fn test_is_valid_commitment() {
+        let data = include_str!("../tests/verify_blob_kzg_proof/verify_blob_kzg_proof_case_correct_proof_fb324bc819407148/data.yaml");
+        let test: Test<BlobInput> = serde_yaml::from_str(data).unwrap();
+        assert!(is_valid_commitment(&test.input.get_commitment().unwrap()));
+
+        // The point at infinity is a valid commitment (to the zero polynomial)
+        let mut infinity = [0u8; 48];
+        infinity[0] = 0xc0;
+        assert!(is_valid_commitment(
+            &Bytes48::from_slice(&infinity).unwrap()
+        ));
+
+        // x = 1 is not the x-coordinate of any point on the curve
+        let off_curve = Bytes48::from_hex("0x800000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000001").unwrap();
+        assert!(!is_valid_commitment(&off_curve));
+
+        // On the curve, but outside of the prime-order subgroup
+        let not_in_subgroup = Bytes48::from_hex("0x8123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef").unwrap();
+        assert!(bool::from(
+            G1Affine::from_compressed_unchecked(&not_in_subgroup.clone().into()).is_some()
+        ));
+        assert!(!is_valid_commitment(&not_in_subgroup));
+    }
+
+    #[test]
+    pub fn test_g1_decompression_errors() {
+        let mut not_on_curve = [0u8; 48];
+        not_on_curve[0] = 0x80;
+        not_on_curve[47] = 1;
+        let not_in_subgroup = Bytes48::from_hex("0x8123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef").unwrap();
+
+        assert!(matches!(
+            safe_g1_affine_from_bytes(&not_on_curve.into()),
+            Err(KzgError::NotOnCurve(_))
+        ));
+        assert!(matches!(
+            safe_g1_affine_from_bytes(&not_in_subgroup),
+            Err(KzgError::NotInSubgroup(_))
+        ));
+        assert!(matches!(
+            g1_affine_from_bytes_with_policy(&not_on_curve.into(), SubgroupPolicy::ClearCofactor),
+            Err(KzgError::NotOnCurve(_))
+        ));
+    }
+
+    #[test]
+    pub fn test_validate_kzg_g1() {
+        let generator: Bytes48 = G1Affine::generator().to_compressed().into();
+        assert_eq!(validate_kzg_g1(&generator).unwrap(), G1Affine::generator());
+        let identity: Bytes48 = G1Affine::identity().to_compressed().into();
+        assert_eq!(validate_kzg_g1(&identity).unwrap(), G1Affine::identity());
+
+        // On the curve, of order dividing the cofactor times r but not r
+        let not_in_subgroup = Bytes48::from_hex("0x8123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef").unwrap();
+        let point = G1Affine::from_compressed_unchecked(&not_in_subgroup.clone().into()).unwrap();
+        assert!(!bool::from(point.is_torsion_free()));
+        assert!(matches!(
+            validate_kzg_g1(&not_in_subgroup),
+            Err(KzgError::NotInSubgroup(_))
+        ));
+    }
+
+    #[test]
+    pub fn test_g1_compression_flag() {
+        let generator: Bytes48 = G1Affine::generator().to_compressed().into();
+        assert!(safe_g1_affine_from_bytes(&generator).is_ok());
+
+        for point in [G1Affine::generator(), G1Affine::identity()] {
+            let mut bytes = point.to_compressed();
+            bytes[0] &= 0x7f;
+            let err = safe_g1_affine_from_bytes(&bytes.into()).unwrap_err();
+            assert!(
+                matches!(&err, KzgError::BadArgs(message) if message.contains("compression flag"))
+            );
+            assert!(!is_valid_commitment(&bytes.into()));
+        }
+    }
+
+    #[test]
+    pub fn test_subgroup_policy() {
+        // On the curve, but outside of the prime-order subgroup
+        let not_in_subgroup = Bytes48::from_hex("0x8123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef").unwrap();
+        let generator: Bytes48 = G1Affine::generator().to_compressed().into();
+        let zero = Bytes32::from([0; 32]);
+
+        assert!(matches!(
+            g1_affine_from_bytes_with_policy(&not_in_subgroup, SubgroupPolicy::Reject),
+            Err(KzgError::NotInSubgroup(_))
+        ));
+        let cleared =
+            g1_affine_from_bytes_with_policy(&not_in_subgroup, SubgroupPolicy::ClearCofactor)
+                .unwrap();
+        assert!(bool::from(cleared.is_torsion_free()));
+        assert!(!bool::from(cleared.is_identity()));
+        // Points already in the subgroup are left as they are
+        assert_eq!(
+            g1_affine_from_bytes_with_policy(&generator, SubgroupPolicy::ClearCofactor).unwrap(),
+            G1Affine::generator()
+        );
+
+        let reject = KzgSettings::load_trusted_setup_file().unwrap();
+        assert_eq!(reject.subgroup_policy, SubgroupPolicy::Reject);
+        assert!(
+            KzgProof::verify_kzg_proof(&not_in_subgroup, &zero, &zero, &generator, &reject)
+                .is_err()
+        );
+
+        let clear_cofactor = KzgSettings::builder()
+            .g1_lagrange(reject.g1_points)
+            .g2_monomial(reject.g2_points)
+            .subgroup_policy(SubgroupPolicy::ClearCofactor)
+            .build()
+            .unwrap();
+        assert!(!KzgProof::verify_kzg_proof(
+            &not_in_subgroup,
+            &zero,
+            &zero,
+            &generator,
+            &clear_cofactor
+        )
+        .unwrap());
+
+        // A zero commitment opens to zero with the identity proof, whatever the policy
+        let mut identity = [0u8; 48];
+        identity[0] = 0xc0;
+        let identity = Bytes48::from(identity);
+        assert!(
+            KzgProof::verify_kzg_proof(&identity, &zero, &zero, &identity, &clear_cofactor)
+                .unwrap()
+        );
+    }
+
+    #[test]
+    pub fn test_malformed_encodings_error_instead_of_panicking() {
+        let kzg_settings = KzgSettings::load_trusted_setup_file().unwrap();
+
+        let mut infinity_with_bits = [0u8; 48];
+        infinity_with_bits[0] = 0xc0;
+        infinity_with_bits[47] = 1;
+        let mut x_above_modulus = [0xffu8; 48];
+        x_above_modulus[0] = 0x9f;
+        let mut x_without_y = [0u8; 48];
+        x_without_y[0] = 0x80;
+        x_without_y[47] = 1;
+        // On the curve, but outside the prime-order subgroup
+        let mut not_in_subgroup = [0u8; 48];
+        not_in_subgroup[0] = 0x80;
+
+        for bytes in [
+            infinity_with_bits,
+            x_above_modulus,
+            x_without_y,
+            not_in_subgroup,
+        ] {
+            let bytes = Bytes48::from(bytes);
+            assert!(safe_g1_affine_from_bytes(&bytes).is_err());
+            assert!(KzgProof::verify_kzg_proof(
+                &bytes,
+                &Bytes32::from([0; 32]),
+                &Bytes32::from([0; 32]),
+                &bytes,
+                &kzg_settings
+            )
+            .is_err());
+        }
+
+        assert!(matches!(
+            safe_scalar_affine_from_bytes(&Bytes32::from([0xff; 32])),
+            Err(KzgError::BadFieldElement(_))
+        ));
+    }
+
+    #[test]
+    pub fn test_parse_field_element_endianness() {
+        let mut encoding = [0u8; 32];
+        encoding[0] = 1;
+        let bytes = Bytes32::from(encoding);
+
+        let little = parse_field_element(&bytes, Endianness::Little).unwrap();
+        let big = parse_field_element(&bytes, Endianness::Big).unwrap();
+        assert_eq!(little, Scalar::one());
+        assert_eq!(big, Scalar::from(2).pow_vartime(&[248, 0, 0, 0]));
+        assert_ne!(little, big);
+
+        for endianness in [Endianness::Big, Endianness::Little] {
+            let encoded = scalar_to_blob_bytes(&little, endianness);
+            assert_eq!(parse_field_element(&encoded, endianness).unwrap(), little);
+        }
+        assert_eq!(
+            scalar_to_blob_bytes(&Scalar::one(), Endianness::Little).as_slice(),
+            bytes.as_slice()
+        );
+
+        // The mainnet settings keep reading blobs as big-endian
+        let kzg_settings = KzgSettings::load_trusted_setup_file().unwrap();
+        assert_eq!(kzg_settings.field_element_endianness, Endianness::Big);
+    }
+
+    #[test]
+    pub fn test_parse_field_element_modulus_boundary() {
+        // MODULUS is stored least significant limb first
+        let mut modulus = [0u8; 32];
+        for (chunk, limb) in modulus.chunks_exact_mut(8).zip(MODULUS.iter().rev()) {
+            chunk.copy_from_slice(&limb.to_be_bytes());
+        }
+
+        let err = parse_field_element(&Bytes32::from(modulus), Endianness::Big).unwrap_err();
+        assert!(matches!(err, KzgError::BadFieldElement(_)));
+
+        let mut modulus_minus_one = modulus;
+        modulus_minus_one[31] -= 1;
+        assert_eq!(
+            parse_field_element(&Bytes32::from(modulus_minus_one), Endianness::Big).unwrap(),
+            -Scalar::one()
+        );
+
+        // The same boundary holds for little-endian blobs
+        modulus.reverse();
+        let err = parse_field_element(&Bytes32::from(modulus), Endianness::Little).unwrap_err();
+        assert!(matches!(err, KzgError::BadFieldElement(_)));
+    }
+
+    #[test]
+    pub fn test_verify_blob_kzg_proof_invalid_blob() {
+        let kzg_settings = KzgSettings::load_trusted_setup_file().unwrap();
+
+        let invalid_blob_tests = VERIFY_BLOB_KZG_PROOF_TESTS
+            .iter()
+            .filter(|(test_file, _)| test_file.contains("invalid_blob"));
+        let mut bad_field_elements = 0;
+        for (_test_file, data) in invalid_blob_tests {
+            let test: Test<BlobInput> = serde_yaml::from_str(data).unwrap();
+            assert!(test.get_output().is_none());
+            // Some vectors have a blob of the wrong length instead
+            let Ok(blob) = test.input.get_blob() else {
+                continue;
+            };
+
+            let result = KzgProof::verify_blob_kzg_proof(
+                blob,
+                &test.input.get_commitment().unwrap(),
+                &test.input.get_proof().unwrap(),
+                &kzg_settings,
+            );
+            assert!(matches!(result, Err(KzgError::BadFieldElement(_))));
+            bad_field_elements += 1;
+        }
+        // One of them holds a field element equal to the modulus exactly
+        assert_eq!(bad_field_elements, 2);
+    }
+
+    #[test]
+    pub fn test_implemented_operations() {
+        let operations = crate::implemented_operations();
+
+        for operation in [
+            "blob_to_kzg_commitment",
+            "compute_kzg_proof",
+            "compute_blob_kzg_proof",
+            "verify_kzg_proof",
+            "verify_blob_kzg_proof",
+            "verify_blob_kzg_proof_batch",
+        ] {
+            assert!(operations.contains(&operation));
+        }
+        // Cells come with the `peerdas` feature only
+        for operation in ["compute_cells", "compute_cells_and_kzg_proofs"] {
+            assert_eq!(operations.contains(&operation), cfg!(feature = "peerdas"));
+        }
+        // The other PeerDAS operations are not available yet
+        assert!(!operations.contains(&"recover_cells_and_kzg_proofs"));
+    }
+
+    #[test]
+    pub fn test_likely_natural_order() {
+        let kzg_settings = KzgSettings::load_trusted_setup_file().unwrap();
+        let field_elements = (0..NUM_FIELD_ELEMENTS_PER_BLOB as u64)
+            .map(|i| scalar_to_blob_bytes(&Scalar::from(i + 1), Endianness::Big))
+            .collect::<Vec<_>>();
+        let blob = Blob::from_field_element_bytes(&field_elements).unwrap();
+        let commitment = blob_to_kzg_commitment(&blob, &kzg_settings).unwrap();
+
+        let mut natural = field_elements.clone();
+        bit_reversal_permutation(&mut natural);
+        let natural = Blob::from_field_element_bytes(&natural).unwrap();
+
+        assert!(likely_natural_order(&natural, &commitment, &kzg_settings));
+        assert!(!likely_natural_order(&blob, &commitment, &kzg_settings));
+        // Neither ordering matches another commitment
+        let other =
+            blob_to_kzg_commitment(&Blob::from_bytes_padded(&[1; 32]).unwrap(), &kzg_settings)
+                .unwrap();
+        assert!(!likely_natural_order(&natural, &other, &kzg_settings));
+    }
+
+    #[test]
+    pub fn test_compute_challenge() {
+        let data = include_str!("../tests/verify_blob_kzg_proof/verify_blob_kzg_proof_case_correct_proof_fb324bc819407148/data.yaml");
+
+        let test: Test<BlobInput> = serde_yaml::from_str(data).unwrap();
+        let blob = test.input.get_blob().unwrap();
+        let commitment = safe_g1_affine_from_bytes(&test.input.get_commitment().unwrap()).unwrap();
+
+        let evaluation_challenge = compute_challenge(&blob, &commitment).unwrap();
+
+        assert_eq!(
+            format!("{evaluation_challenge}"),
+            "0x4f00eef944a21cb9f3ac3390702621e4bbf1198767c43c0fb9c8e9923bfbb31a"
+        );
+
+        // The mainnet settings carry the EIP-4844 domain separators
+        let kzg_settings = KzgSettings::load_trusted_setup_file().unwrap();
+        assert_eq!(
+            kzg_settings.fiat_shamir_domain,
+            FIAT_SHAMIR_PROTOCOL_DOMAIN.as_bytes()
+        );
+        assert_eq!(
+            kzg_settings.batch_domain,
+            RANDOM_CHALLENGE_KZG_BATCH_DOMAIN.as_bytes()
+        );
+        assert_eq!(
+            compute_challenge_with_domain(&blob, &commitment, kzg_settings.fiat_shamir_domain)
+                .unwrap(),
+            evaluation_challenge
+        );
+    }
+
+    #[test]
+    pub fn test_custom_domain_separators() {
+        let kzg_settings = KzgSettings::load_trusted_setup_file().unwrap();
+        let forked = KzgSettings::builder()
+            .g1_lagrange(kzg_settings.g1_points)
+            .g2_monomial(kzg_settings.g2_points)
+            .fiat_shamir_domain(*b"FORKEDVERIFY_V1_")
+            .batch_domain(*b"FORKEDBATCH__V1_")
+            .build()
+            .unwrap();
+        let builder_default = KzgSettings::builder()
+            .g1_lagrange(kzg_settings.g1_points)
+            .g2_monomial(kzg_settings.g2_points)
+            .build()
+            .unwrap();
+        assert_eq!(builder_default, kzg_settings);
+
+        let blob = Blob::from_bytes_padded(&[1; 64]).unwrap();
+        let commitment_bytes = blob_to_kzg_commitment(&blob, &kzg_settings).unwrap();
+        let commitment = safe_g1_affine_from_bytes(&commitment_bytes).unwrap();
+        let z = compute_challenge(&blob, &commitment).unwrap();
+        let forked_z =
+            compute_challenge_with_domain(&blob, &commitment, forked.fiat_shamir_domain).unwrap();
+        assert_ne!(z, forked_z);
+
+        // Proofs are bound to the domain they were computed with
+        let proof = compute_blob_kzg_proof(&blob, &commitment_bytes, &forked).unwrap();
+        assert!(
+            KzgProof::verify_blob_kzg_proof(blob.clone(), &commitment_bytes, &proof, &forked)
+                .unwrap()
+        );
+        assert!(!KzgProof::verify_blob_kzg_proof(
+            blob.clone(),
+            &commitment_bytes,
+            &proof,
+            &kzg_settings
+        )
+        .unwrap());
+        let y = blob
+            .as_polynomial_with_endianness(Endianness::Big)
+            .and_then(|polynomial| {
+                evaluate_polynomial_in_evaluation_form(polynomial, forked_z, &forked)
+            })
+            .unwrap();
+
+        let batch_challenge = |settings: &KzgSettings| {
+            compute_batch_challenge(&[commitment], &[forked_z], &[y], &[commitment], settings)
+                .unwrap()
+        };
+        assert_ne!(batch_challenge(&kzg_settings), batch_challenge(&forked));
+    }
+
+    /// Reference barycentric evaluation doing one inversion per domain point
+    fn evaluate_polynomial_naive(polynomial: &[Scalar], x: Scalar, roots: &[Scalar]) -> Scalar {
+        if let Some(i) = roots.iter().position(|root| *root == x) {
+            return polynomial[i];
+        }
+
+        let n = Scalar::from(polynomial.len() as u64);
+        let sum = polynomial
+            .iter()
+            .zip(roots)
+            .fold(Scalar::zero(), |acc, (p, root)| {
+                acc + p * root * (x - root).invert().unwrap()
+            });
+
+        sum * n.invert().unwrap() * (x.pow(&[polynomial.len() as u64, 0, 0, 0]) - Scalar::one())
+    }
+
+    #[test]
+    pub fn test_interpolate_on_domain() {
+        let kzg_settings = generate_insecure_setup(Scalar::from(0x1234_5678), 16);
+        let evaluations = Polynomial::try_from([1, 2, 3].map(Scalar::from).as_slice())
+            .unwrap()
+            .to_evaluations(&kzg_settings)
+            .unwrap();
+
+        // Every position, in any order
+        let mut values = evaluations.iter().copied().enumerate().collect::<Vec<_>>();
+        values.swap(0, 9);
+        values.swap(3, 14);
+        assert_eq!(
+            interpolate_on_domain(&values, &kzg_settings).unwrap(),
+            evaluations
+        );
+
+        // Fewer positions are underdetermined, even for a quadratic
+        let known = [
+            (7, evaluations[7]),
+            (0, evaluations[0]),
+            (12, evaluations[12]),
+        ];
+        for values in [&known[..], &[], &values[1..]] {
+            assert!(matches!(
+                interpolate_on_domain(values, &kzg_settings),
+                Err(KzgError::BadArgs(_))
+            ));
+        }
+
+        let mut too_many = values.clone();
+        too_many.push((0, Scalar::one()));
+        assert!(matches!(
+            interpolate_on_domain(&too_many, &kzg_settings),
+            Err(KzgError::MismatchedLengths {
+                expected: 16,
+                got: 17
+            })
+        ));
+
+        let mut out_of_range = values.clone();
+        out_of_range[5].0 = 16;
+        assert!(interpolate_on_domain(&out_of_range, &kzg_settings).is_err());
+        let mut duplicated = values;
+        duplicated[5].0 = duplicated[6].0;
+        assert!(interpolate_on_domain(&duplicated, &kzg_settings).is_err());
+    }
+
+    #[test]
+    pub fn test_interpolate_on_domain_full_set_is_identity() {
+        let data = include_str!("../tests/verify_blob_kzg_proof/verify_blob_kzg_proof_case_correct_proof_19b3f3f8c98ea31e/data.yaml");
+        let test: Test<BlobInput> = serde_yaml::from_str(data).unwrap();
+        let kzg_settings = KzgSettings::load_trusted_setup_file().unwrap();
+        let polynomial = test.input.get_blob().unwrap().as_polynomial().unwrap();
+
+        let values = polynomial
+            .iter()
+            .copied()
+            .enumerate()
+            .rev()
+            .collect::<Vec<_>>();
+        assert_eq!(
+            interpolate_on_domain(&values, &kzg_settings).unwrap(),
+            polynomial
+        );
+    }
+
+    #[test]
+    pub fn test_evaluate_polynomial_matches_naive_inversion() {
+        let kzg_settings = KzgSettings::load_trusted_setup_file().unwrap();
+
+        for (_test_file, data) in VERIFY_BLOB_KZG_PROOF_TESTS.iter().take(4) {
+            let test: Test<BlobInput> = serde_yaml::from_str(data).unwrap();
+            let Ok(polynomial) = test.input.get_blob().and_then(|blob| blob.as_polynomial()) else {
+                continue;
+            };
+
+            for x in [
+                Scalar::from(7),
+                scalar_from_bytes_unchecked([0x5a; 32]),
+                kzg_settings.roots_of_unity[42],
+            ] {
+                let expected =
+                    evaluate_polynomial_naive(&polynomial, x, kzg_settings.roots_of_unity);
+                let y =
+                    evaluate_polynomial_in_evaluation_form(polynomial.clone(), x, &kzg_settings)
+                        .unwrap();
+                assert_eq!(y, expected);
+            }
+        }
+    }
+
+    #[test]
+    #[ignore = "benchmark, run with `cargo test --release -- --ignored --nocapture`"]
+    pub fn bench_evaluate_polynomial_batch_vs_naive_inversion() {
+        use std::time::Instant;
+
+        const ITERATIONS: u32 = 10;
+
+        let data = include_str!("../tests/verify_blob_kzg_proof/verify_blob_kzg_proof_case_correct_proof_19b3f3f8c98ea31e/data.yaml");
+        let test: Test<BlobInput> = serde_yaml::from_str(data).unwrap();
+        let kzg_settings = KzgSettings::load_trusted_setup_file().unwrap();
+        let polynomial = test.input.get_blob().unwrap().as_polynomial().unwrap();
+        let x = scalar_from_bytes_unchecked([0x5a; 32]);
+
+        let start = Instant::now();
+        for _ in 0..ITERATIONS {
+            evaluate_polynomial_in_evaluation_form(polynomial.clone(), x, &kzg_settings).unwrap();
+        }
+        let batched = start.elapsed() / ITERATIONS;
+
+        let start = Instant::now();
+        for _ in 0..ITERATIONS {
+            evaluate_polynomial_naive(&polynomial, x, kzg_settings.roots_of_unity);
+        }
+        let naive = start.elapsed() / ITERATIONS;
+
+        println!("batch inversion: {batched:?}, per-point inversion: {naive:?}");
+        assert!(batched < naive);
+    }
+
+    /// The original challenge derivation, hashing one contiguous buffer
+    fn compute_challenge_buffered(blob: &Blob, commitment: &G1Affine) -> Scalar {
+        use crate::{CHALLENGE_INPUT_SIZE, DOMAIN_STR_LENGTH};
+
+        let mut bytes = vec![0_u8; CHALLENGE_INPUT_SIZE];
+        let mut offset = 0_usize;
+        bytes[offset..DOMAIN_STR_LENGTH].copy_from_slice(FIAT_SHAMIR_PROTOCOL_DOMAIN.as_bytes());
+        offset += DOMAIN_STR_LENGTH;
+        bytes[offset..offset + 8].copy_from_slice(&0_u64.to_be_bytes());
+        offset += 8;
+        bytes[offset..offset + 8]
+            .copy_from_slice(&(NUM_FIELD_ELEMENTS_PER_BLOB as u64).to_be_bytes());
+        offset += 8;
+        bytes[offset..offset + BYTES_PER_BLOB].copy_from_slice(blob.as_slice());
+        offset += BYTES_PER_BLOB;
+        bytes[offset..offset + BYTES_PER_COMMITMENT].copy_from_slice(&commitment.to_compressed());
+        offset += BYTES_PER_COMMITMENT;
+        assert_eq!(offset, CHALLENGE_INPUT_SIZE);
+
+        scalar_from_bytes_unchecked(Sha256::digest(bytes).into())
+    }
+
+    #[test]
+    pub fn test_streamed_challenge_matches_buffered() {
+        for (_test_file, data) in VERIFY_BLOB_KZG_PROOF_TESTS.iter().take(4) {
+            let test: Test<BlobInput> = serde_yaml::from_str(data).unwrap();
+            let (Ok(blob), Ok(commitment)) = (
+                test.input.get_blob(),
+                test.input
+                    .get_commitment()
+                    .and_then(|commitment| safe_g1_affine_from_bytes(&commitment)),
+            ) else {
+                continue;
+            };
+
+            let expected = compute_challenge_buffered(&blob, &commitment);
+            assert_eq!(compute_challenge(&blob, &commitment).unwrap(), expected);
+
+            // Unevenly sized chunks straddling field element boundaries
+            let mut hasher = ChallengeHasher::new();
+            for chunk in blob.as_slice().chunks(1000) {
+                hasher.update(chunk).unwrap();
+            }
+            assert_eq!(hasher.finalize(&commitment).unwrap(), expected);
+
+            // Too little or too much blob data
+            let mut hasher = ChallengeHasher::new();
+            hasher.update(&blob.as_slice()[1..]).unwrap();
+            assert!(hasher.clone().finalize(&commitment).is_err());
+            hasher.update(&[0, 0]).unwrap_err();
+        }
+    }
+
+    #[test]
+    pub fn test_verify_kzg_proof_toy_setup() {
+        let tau = Scalar::from(0x1234_5678);
+        let kzg_settings = generate_insecure_setup(tau, 4);
+        assert_eq!(kzg_settings.field_elements_per_blob(), 4);
+
+        let polynomial = [1, 2, 3, 4].map(Scalar::from).to_vec();
+        let commitment = poly_to_kzg_commitment(&polynomial, &kzg_settings).unwrap();
+
+        let z = Scalar::from(99);
+        let y =
+            evaluate_polynomial_in_evaluation_form(polynomial.clone(), z, &kzg_settings).unwrap();
+        // The quotient (p(X) - y) / (X - z) evaluated at the secret
+        let p_tau =
+            evaluate_polynomial_in_evaluation_form(polynomial.clone(), tau, &kzg_settings).unwrap();
+        let quotient = (p_tau - y) * (tau - z).invert().unwrap();
+        let proof = G1Affine::from(G1Affine::generator() * quotient);
+
+        assert!(verify_kzg_proof_impl(commitment, z, y, proof, &kzg_settings).unwrap());
+        assert!(
+            !verify_kzg_proof_impl(commitment, z, y + Scalar::one(), proof, &kzg_settings).unwrap()
+        );
+        assert!(KzgProof::verify_kzg_proof_batch(
+            &[commitment, commitment],
+            &[z, z],
+            &[y, y],
+            &[proof, proof],
+            &kzg_settings
+        )
+        .unwrap());
+
+        // Polynomials sized for the EIP-4844 setup are rejected
+        let blob_sized = vec![Scalar::zero(); NUM_FIELD_ELEMENTS_PER_BLOB];
+        assert!(poly_to_kzg_commitment(&blob_sized, &kzg_settings).is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "reference")]
+    pub fn test_verify_kzg_proof_matches_two_pairings_reference() {
+        let tau = Scalar::from(0x1234_5678);
+        let kzg_settings = generate_insecure_setup(tau, 4);
+        // Deterministic pseudo-random scalars
+        let scalar =
+            |seed: u64| scalar_from_bytes_unchecked(Sha256::digest(seed.to_be_bytes()).into());
+        let to_bytes48 = |point: G1Affine| Bytes48::from(point.to_compressed());
+
+        for i in 0..32u64 {
+            let polynomial = (0..4).map(|j| scalar(i * 8 + j)).collect::<Vec<_>>();
+            let commitment = poly_to_kzg_commitment(&polynomial, &kzg_settings).unwrap();
+            let z = scalar(i * 8 + 5);
+            let (proof, y) = compute_kzg_proof_impl(&polynomial, z, &kzg_settings).unwrap();
+
+            // The valid opening, then a wrong evaluation, a wrong proof and a wrong commitment
+            let cases = [
+                (commitment, y, proof),
+                (commitment, y + scalar(i * 8 + 6), proof),
+                (
+                    commitment,
+                    y,
+                    G1Affine::from(G1Projective::from(proof) + G1Affine::generator()),
+                ),
+                (
+                    G1Affine::from(G1Affine::generator() * scalar(i * 8 + 7)),
+                    y,
+                    proof,
+                ),
+            ];
+            for (case, (commitment, y, proof)) in cases.into_iter().enumerate() {
+                let commitment = to_bytes48(commitment);
+                let z = scalar_to_blob_bytes(&z, Endianness::Big);
+                let y = scalar_to_blob_bytes(&y, Endianness::Big);
+                let proof = to_bytes48(proof);
+
+                let reference = KzgProof::verify_kzg_proof_two_pairings(
+                    &commitment,
+                    &z,
+                    &y,
+                    &proof,
+                    &kzg_settings,
+                )
+                .unwrap();
+                assert_eq!(reference, case == 0);
+                assert_eq!(
+                    KzgProof::verify_kzg_proof(&commitment, &z, &y, &proof, &kzg_settings).unwrap(),
+                    reference
+                );
+            }
+        }
+    }
+
+    #[test]
+    pub fn test_compute_kzg_proofs_multi() {
+        let data = include_str!("../tests/verify_blob_kzg_proof/verify_blob_kzg_proof_case_correct_proof_19b3f3f8c98ea31e/data.yaml");
+        let test: Test<BlobInput> = serde_yaml::from_str(data).unwrap();
+        let kzg_settings = KzgSettings::load_trusted_setup_file().unwrap();
+        let blob = test.input.get_blob().unwrap();
+        let commitment = test.input.get_commitment().unwrap();
+        let polynomial = blob.as_polynomial().unwrap();
+
+        // Off the domain, and on it to exercise the limit branch of the quotient
+        let zs = [
+            Scalar::from(7),
+            scalar_from_bytes_unchecked([0x5a; 32]),
+            kzg_settings.roots_of_unity[42],
+        ];
+        let zs_bytes = zs
+            .iter()
+            .map(|z| scalar_to_blob_bytes(z, Endianness::Big))
+            .collect::<Vec<_>>();
+
+        let openings = KzgProof::compute_kzg_proofs_multi(&blob, &zs_bytes, &kzg_settings).unwrap();
+        assert_eq!(openings.len(), zs.len());
+
+        for ((z, z_bytes), (proof, y)) in zs.iter().zip(&zs_bytes).zip(&openings) {
+            let (expected_proof, expected_y) =
+                compute_kzg_proof_impl(&polynomial, *z, &kzg_settings).unwrap();
+            assert_eq!(proof.as_slice(), expected_proof.to_compressed());
+            assert_eq!(
+                safe_scalar_affine_from_bytes(y).unwrap(),
+                evaluate_polynomial_in_evaluation_form(polynomial.clone(), *z, &kzg_settings)
+                    .unwrap()
+            );
+            assert_eq!(safe_scalar_affine_from_bytes(y).unwrap(), expected_y);
+
+            assert!(
+                KzgProof::verify_kzg_proof(&commitment, z_bytes, y, proof, &kzg_settings).unwrap()
+            );
+        }
+
+        // A non-canonical point fails the whole call
+        assert!(KzgProof::compute_kzg_proofs_multi(
+            &blob,
+            &[Bytes32::from([0xff; 32])],
+            &kzg_settings
+        )
+        .is_err());
+    }
+
+    #[test]
+    pub fn test_commit_single() {
+        let kzg_settings = KzgSettings::load_trusted_setup_file().unwrap();
+
+        let mut polynomial = vec![Scalar::zero(); NUM_FIELD_ELEMENTS_PER_BLOB];
+        let entries = [(0, 11), (1, 22), (42, 33), (4095, 44)];
+        for (index, value) in entries {
+            polynomial[index] = Scalar::from(value);
+        }
+
+        let singles = entries
+            .iter()
+            .map(|&(index, value)| commit_single(index, &Scalar::from(value), &kzg_settings))
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(
+            try_sum_commitments(&singles).unwrap(),
+            KzgCommitment::from(poly_to_kzg_commitment(&polynomial, &kzg_settings).unwrap())
+        );
+
+        assert!(commit_single(NUM_FIELD_ELEMENTS_PER_BLOB, &Scalar::one(), &kzg_settings).is_err());
+    }
+
+    #[test]
+    pub fn test_blob_to_kzg_commitment() {
+        let kzg_settings = KzgSettings::load_trusted_setup_file().unwrap();
+
+        let mut checked = 0;
+        for (_test_file, data) in VERIFY_BLOB_KZG_PROOF_TESTS.iter().take(16) {
+            let test: Test<BlobInput> = serde_yaml::from_str(data).unwrap();
+            let (Ok(blob), Ok(commitment), Some(true)) = (
+                test.input.get_blob(),
+                test.input.get_commitment(),
+                test.get_output(),
+            ) else {
+                continue;
+            };
+            assert_eq!(
+                blob_to_kzg_commitment(&blob, &kzg_settings)
+                    .unwrap()
+                    .as_slice(),
+                commitment.as_slice()
+            );
+            checked += 1;
+        }
+        assert!(checked > 0);
+
+        // A field element above the modulus
+        let blob = Blob::from_slice(&[0xff; BYTES_PER_BLOB]).unwrap();
+        assert!(matches!(
+            blob_to_kzg_commitment(&blob, &kzg_settings),
+            Err(KzgError::BadFieldElement(_))
+        ));
+    }
+
+    #[test]
+    pub fn test_compute_kzg_proof() {
+        let data = include_str!("../tests/verify_blob_kzg_proof/verify_blob_kzg_proof_case_correct_proof_19b3f3f8c98ea31e/data.yaml");
+        let test: Test<BlobInput> = serde_yaml::from_str(data).unwrap();
+        let kzg_settings = KzgSettings::load_trusted_setup_file().unwrap();
+        let blob = test.input.get_blob().unwrap();
+        let commitment = test.input.get_commitment().unwrap();
+        let polynomial = blob.as_polynomial().unwrap();
+
+        // Off the domain, and on it where the quotient has to take the limit
+        for z in [Scalar::from(1234), kzg_settings.roots_of_unity[7]] {
+            let z_bytes = scalar_to_blob_bytes(&z, Endianness::Big);
+            let (proof, y) = compute_kzg_proof(&blob, &z_bytes, &kzg_settings).unwrap();
+
+            assert_eq!(
+                safe_scalar_affine_from_bytes(&y).unwrap(),
+                evaluate_polynomial_in_evaluation_form(polynomial.clone(), z, &kzg_settings)
+                    .unwrap()
+            );
+            assert!(
+                KzgProof::verify_kzg_proof(&commitment, &z_bytes, &y, &proof, &kzg_settings)
+                    .unwrap()
+            );
+        }
+
+        assert!(matches!(
+            compute_kzg_proof(&blob, &Bytes32::from([0xff; 32]), &kzg_settings),
+            Err(KzgError::BadFieldElement(_))
+        ));
+    }
+
+    #[test]
+    pub fn test_compute_blob_kzg_proof() {
+        let kzg_settings = KzgSettings::load_trusted_setup_file().unwrap();
+
+        let (blobs, commitments, proofs) = valid_blob_vectors(4);
+        for ((blob, commitment), proof) in blobs.iter().zip(&commitments).zip(&proofs) {
+            assert_eq!(
+                compute_blob_kzg_proof(blob, commitment, &kzg_settings)
+                    .unwrap()
+                    .as_slice(),
+                proof.as_slice()
+            );
+        }
+
+        let blob = Blob::zero();
+        let commitment = blob_to_kzg_commitment(&blob, &kzg_settings).unwrap();
+        let proof = compute_blob_kzg_proof(&blob, &commitment, &kzg_settings).unwrap();
+        assert!(KzgProof::verify_blob_kzg_proof(blob, &commitment, &proof, &kzg_settings).unwrap());
+
+        let mut not_on_curve = [0u8; 48];
+        not_on_curve[0] = 0x80;
+        not_on_curve[47] = 1;
+        assert!(matches!(
+            compute_blob_kzg_proof(&Blob::zero(), &not_on_curve.into(), &kzg_settings),
+            Err(KzgError::NotOnCurve(message)) if message.starts_with("The commitment is not")
+        ));
+    }
+
+    /// Checks the crate reproduces a JSON dump of `{blob, commitment, proof}` entries from another
+    /// implementation, panicking on the first mismatch
+    pub fn run_reference_dump(json: &str, kzg_settings: &KzgSettings) {
+        // JSON is a subset of YAML, which spares a JSON parser just for tests
+        let entries: Vec<BlobInput> = serde_yaml::from_str(json).unwrap();
+        assert!(!entries.is_empty(), "The reference dump has no entries");
+
+        for (i, entry) in entries.iter().enumerate() {
+            let context = format!("entry {} (blob {}…)", i, &entry.blob[..18]);
+            let blob = entry.get_blob().unwrap();
+
+            let commitment = blob_to_kzg_commitment(&blob, kzg_settings)
+                .unwrap_or_else(|e| panic!("{}: committing failed: {}", context, e));
+            assert_eq!(
+                hex::encode(commitment.as_slice()),
+                hex::encode(entry.get_commitment().unwrap().as_slice()),
+                "{}: commitment mismatch",
+                context
+            );
+
+            let proof = compute_blob_kzg_proof(&blob, &commitment, kzg_settings)
+                .unwrap_or_else(|e| panic!("{}: proving failed: {}", context, e));
+            assert_eq!(
+                hex::encode(proof.as_slice()),
+                hex::encode(entry.get_proof().unwrap().as_slice()),
+                "{}: proof mismatch",
+                context
+            );
+        }
+    }
+
+    #[test]
+    pub fn test_reference_dump() {
+        let kzg_settings = KzgSettings::load_trusted_setup_file().unwrap();
+        run_reference_dump(include_str!("../tests/reference_dump.json"), &kzg_settings);
+    }
+
+    #[test]
+    #[should_panic(expected = "entry 0 (blob 0x0000000000000000…): commitment mismatch")]
+    pub fn test_reference_dump_reports_mismatch() {
+        let kzg_settings = KzgSettings::load_trusted_setup_file().unwrap();
+        let dump = include_str!("../tests/reference_dump.json").replacen(
+            "0xc00000000000",
+            "0xc00000000001",
+            1,
+        );
+        run_reference_dump(&dump, &kzg_settings);
+    }
+
+    #[test]
+    pub fn test_blob_to_commitment_and_hash() {
+        let kzg_settings = KzgSettings::load_trusted_setup_file().unwrap();
+
+        let (commitment, versioned_hash) =
+            blob_to_commitment_and_hash(&Blob::zero(), &kzg_settings).unwrap();
+        assert_eq!(commitment, KzgCommitment::identity());
+        // Versioned hash of the empty blob, as seen on mainnet
+        assert_eq!(
+            hex::encode(versioned_hash),
+            "010657f37554c781402a22917dee2f75def7ab966d7b770905398eba3c444014"
+        );
+
+        for (_test_file, data) in VERIFY_BLOB_KZG_PROOF_TESTS.iter().take(8) {
+            let test: Test<BlobInput> = serde_yaml::from_str(data).unwrap();
+            let Ok(blob) = test.input.get_blob() else {
+                continue;
+            };
+            let Ok(separate) = blob_to_kzg_commitment(&blob, &kzg_settings) else {
+                assert!(blob_to_commitment_and_hash(&blob, &kzg_settings).is_err());
+                continue;
+            };
+            let separate = KzgCommitment::from(separate);
+            let (commitment, versioned_hash) =
+                blob_to_commitment_and_hash(&blob, &kzg_settings).unwrap();
+            assert_eq!(commitment, separate);
+            assert_eq!(versioned_hash, kzg_commitment_to_versioned_hash(&separate));
+            assert_eq!(versioned_hash[0], VERSIONED_HASH_VERSION_KZG);
+        }
+    }
+
+    #[test]
+    pub fn test_pack_proofs_roundtrip() {
+        let proofs: Vec<Bytes48> = (1..=5u64)
+            .map(|i| {
+                G1Affine::from(G1Affine::generator() * Scalar::from(i))
+                    .to_compressed()
+                    .into()
+            })
+            .collect();
+
+        let packed = pack_proofs(&proofs);
+        assert_eq!(packed.len(), proofs.len() * BYTES_PER_PROOF);
+        assert_eq!(
+            &packed[BYTES_PER_PROOF..2 * BYTES_PER_PROOF],
+            proofs[1].as_slice()
+        );
+        let unpacked = unpack_proofs(&packed).unwrap();
+        assert_eq!(unpacked.len(), proofs.len());
+        for (unpacked, proof) in unpacked.iter().zip(&proofs) {
+            assert_eq!(unpacked.as_slice(), proof.as_slice());
+        }
+
+        assert!(pack_proofs(&[]).is_empty());
+        assert!(unpack_proofs(&[]).unwrap().is_empty());
+        assert!(matches!(
+            unpack_proofs(&packed[..packed.len() - 1]),
+            Err(KzgError::InvalidBytesLength(_))
+        ));
+    }
+
+    #[test]
+    pub fn test_check_on_domain_evaluation() {
+        let data = include_str!("../tests/verify_blob_kzg_proof/verify_blob_kzg_proof_case_correct_proof_19b3f3f8c98ea31e/data.yaml");
+        let test: Test<BlobInput> = serde_yaml::from_str(data).unwrap();
+        let kzg_settings = KzgSettings::load_trusted_setup_file().unwrap();
+        let polynomial = test.input.get_blob().unwrap().as_polynomial().unwrap();
+
+        let z = kzg_settings.roots_of_unity[42];
+        let y = polynomial[42];
+        assert!(check_on_domain_evaluation(&polynomial, &z, &y, &kzg_settings).is_ok());
+        assert!(
+            check_on_domain_evaluation(&polynomial, &z, &(y + Scalar::one()), &kzg_settings)
+                .is_err()
+        );
+
+        // Off the domain there is nothing to compare against
+        assert!(check_on_domain_evaluation(
+            &polynomial,
+            &Scalar::from(7),
+            &Scalar::zero(),
+            &kzg_settings
+        )
+        .is_ok());
+    }
+
+    #[test]
+    pub fn test_commitment_accumulator() {
+        let data = include_str!("../tests/verify_blob_kzg_proof/verify_blob_kzg_proof_case_correct_proof_19b3f3f8c98ea31e/data.yaml");
+        let test: Test<BlobInput> = serde_yaml::from_str(data).unwrap();
+        let kzg_settings = KzgSettings::load_trusted_setup_file().unwrap();
+        let blob = test.input.get_blob().unwrap();
+        let polynomial = blob.as_polynomial().unwrap();
+
+        let mut accumulator = CommitmentAccumulator::new();
+        for (index, value) in polynomial.iter().enumerate() {
+            accumulator
+                .add_element(index, value, &kzg_settings)
+                .unwrap();
+        }
+        let commitment = accumulator.finish();
+        assert_eq!(
+            commitment,
+            KzgCommitment::from(blob_to_kzg_commitment(&blob, &kzg_settings).unwrap())
+        );
+        assert_eq!(
+            commitment.as_bytes().as_slice(),
+            test.input.get_commitment().unwrap().as_slice()
+        );
+
+        assert_eq!(
+            CommitmentAccumulator::new().finish(),
+            KzgCommitment::identity()
+        );
+        assert!(CommitmentAccumulator::new()
+            .add_element(NUM_FIELD_ELEMENTS_PER_BLOB, &Scalar::one(), &kzg_settings)
+            .is_err());
+    }
+
+    #[test]
+    pub fn test_commitment_delta() {
+        let kzg_settings = KzgSettings::load_trusted_setup_file().unwrap();
+
+        let blob = |seed: u64| {
+            let field_elements = (0..NUM_FIELD_ELEMENTS_PER_BLOB as u64)
+                .map(|i| scalar_to_blob_bytes(&Scalar::from(i * seed + 1), Endianness::Big))
+                .collect::<Vec<_>>();
+            Blob::from_field_element_bytes(&field_elements).unwrap()
+        };
+        let (a, b) = (blob(3), blob(5));
+
+        let commit = |blob: &Blob| {
+            poly_to_kzg_commitment(&blob.as_polynomial().unwrap(), &kzg_settings).unwrap()
+        };
+        let expected = try_sum_commitments(&[commit(&a).into(), (-commit(&b)).into()]).unwrap();
+
+        assert_eq!(commitment_delta(&a, &b, &kzg_settings).unwrap(), expected);
+        // An unedited blob has the commitment to zero, the point at infinity
+        assert!(bool::from(
+            commitment_delta(&a, &a, &kzg_settings)
+                .unwrap()
+                .to_g1()
+                .unwrap()
+                .is_identity()
+        ));
+    }
+
+    #[test]
+    pub fn test_verify_kzg_proof_extended_g2_setup() {
+        let tau = Scalar::from(0xdead_beef);
+        let num_g2_points = 2 * crate::NUM_G2_POINTS;
+        let g2_points = compute_powers(&tau, num_g2_points)
+            .iter()
+            .map(|power| G2Affine::from(G2Affine::generator() * power))
+            .collect::<Vec<_>>();
+        let toy = generate_insecure_setup(tau, 4);
+        let kzg_settings = KzgSettings::builder()
+            .g1_lagrange(toy.g1_points)
+            .g2_monomial(g2_points.leak())
+            .roots_of_unity(toy.roots_of_unity)
+            .build()
+            .unwrap();
+        assert_eq!(kzg_settings.g2_points.len(), num_g2_points);
+
+        // Every extra point continues the monomial sequence: e([tau]G1, [tau^i]G2) = e(G1, [tau^(i+1)]G2)
+        let tau_g1 = G1Affine::from(G1Affine::generator() * tau);
+        for window in kzg_settings.g2_points.windows(2) {
+            assert!(pairings_verify(
+                tau_g1,
+                window[0],
+                G1Affine::generator(),
+                window[1]
+            ));
+        }
+
+        // The EIP-4844 path ignores the extra points
+        let polynomial = [5, 6, 7, 8].map(Scalar::from).to_vec();
+        let commitment = poly_to_kzg_commitment(&polynomial, &kzg_settings).unwrap();
+        let z = Scalar::from(3);
+        let y =
+            evaluate_polynomial_in_evaluation_form(polynomial.clone(), z, &kzg_settings).unwrap();
+        let p_tau = evaluate_polynomial_in_evaluation_form(polynomial, tau, &kzg_settings).unwrap();
+        let proof =
+            G1Affine::from(G1Affine::generator() * ((p_tau - y) * (tau - z).invert().unwrap()));
+        assert!(verify_kzg_proof_impl(commitment, z, y, proof, &kzg_settings).unwrap());
     }
 
     #[test]
@@ -1,22 +1,75 @@
 #![cfg_attr(not(test), no_std)]
+// Without `unsafe-opt` the crate has no `unsafe` at all, which every default build checks. Tests
+// only deny it, so the counting allocator of the allocation tests can opt out.
+#![cfg_attr(not(any(test, feature = "unsafe-opt")), forbid(unsafe_code))]
+#![cfg_attr(all(test, not(feature = "unsafe-opt")), deny(unsafe_code))]
 #[macro_use]
 extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
 
+/// Adds to the thread-local [`op_counts::OpCounts`], compiled out without the `op-counts` feature
+macro_rules! count_ops {
+    ($($field:ident += $n:expr),+ $(,)?) => {
+        #[cfg(feature = "op-counts")]
+        $crate::op_counts::record(|counts| {
+            $(counts.$field += $n;)+
+        });
+    };
+}
+
+#[cfg(feature = "peerdas")]
+pub mod cells;
 pub mod consts;
 pub mod dtypes;
 pub mod enums;
+pub mod fft;
 pub mod kzg_proof;
+pub mod msm;
+#[cfg(feature = "op-counts")]
+pub mod op_counts;
 pub mod pairings;
+pub mod setup_encoding;
+pub mod sidecar;
 pub mod trusted_setup;
 
+#[cfg(feature = "peerdas")]
+pub use cells::{
+    compute_blob_and_cell_proofs, compute_cells, compute_cells_and_kzg_proofs,
+    compute_cells_from_polynomial,
+};
 pub use consts::*;
 pub use dtypes::*;
-pub use kzg_proof::KzgProof;
-pub use pairings::pairings_verify;
+pub use kzg_proof::{
+    batch_preflight, blob_to_commitment_and_hash, blob_to_kzg_commitment, compute_blob_kzg_proof,
+    compute_kzg_proof, kzg_commitment_to_versioned_hash, likely_natural_order, pack_proofs,
+    safe_g2_affine_from_bytes, unpack_proofs, validate_kzg_g1, KzgProof, VerifyStats,
+};
+pub use pairings::{
+    pairings_verify, pairings_verify_ct, pairings_verify_prepared_ct, PairingCheck,
+};
+pub use sidecar::BlobSidecar;
 pub use trusted_setup::*;
 
 pub use enums::KzgError;
 
+/// Names of the c-kzg test-vector operations this build of the crate can run, so generic test
+/// runners can skip the vectors of everything else.
+pub fn implemented_operations() -> &'static [&'static str] {
+    &[
+        "blob_to_kzg_commitment",
+        "compute_kzg_proof",
+        "compute_blob_kzg_proof",
+        #[cfg(feature = "peerdas")]
+        "compute_cells",
+        #[cfg(feature = "peerdas")]
+        "compute_cells_and_kzg_proofs",
+        "verify_kzg_proof",
+        "verify_blob_kzg_proof",
+        "verify_blob_kzg_proof_batch",
+    ]
+}
+
 #[cfg(test)]
 mod test_files {
     // Tests
@@ -511,138 +564,102 @@ mod test_files {
         ),
     ];
 
-    pub const VERIFY_BLOB_KZG_PROOF_BATCH_TESTS: [(&str, &str); 27] = [
-        (
-            "verify_blob_kzg_proof_case_correct_proof_0951cfd9ab47a8d3",
-            include_str!("../tests/verify_blob_kzg_proof/verify_blob_kzg_proof_case_correct_proof_0951cfd9ab47a8d3/data.yaml"),
-        ),
-        (
-            "verify_blob_kzg_proof_case_correct_proof_19b3f3f8c98ea31e",
-            include_str!("../tests/verify_blob_kzg_proof/verify_blob_kzg_proof_case_correct_proof_19b3f3f8c98ea31e/data.yaml"),
-        ),
-        (
-            "verify_blob_kzg_proof_case_correct_proof_84d8089232bc23a8",
-            include_str!("../tests/verify_blob_kzg_proof/verify_blob_kzg_proof_case_correct_proof_84d8089232bc23a8/data.yaml"),
-        ),
+    pub const VERIFY_BLOB_KZG_PROOF_BATCH_TESTS: [(&str, &str); 24] = [
         (
-            "verify_blob_kzg_proof_case_correct_proof_a87a4e636e0f58fb",
-            include_str!("../tests/verify_blob_kzg_proof/verify_blob_kzg_proof_case_correct_proof_a87a4e636e0f58fb/data.yaml"),
+            "verify_blob_kzg_proof_batch_case_0951cfd9ab47a8d3",
+            include_str!("../tests/verify_blob_kzg_proof_batch/verify_blob_kzg_proof_batch_case_0951cfd9ab47a8d3/data.yaml"),
         ),
         (
-            "verify_blob_kzg_proof_case_correct_proof_c40b9b515df8721b",
-            include_str!("../tests/verify_blob_kzg_proof/verify_blob_kzg_proof_case_correct_proof_c40b9b515df8721b/data.yaml"),
+            "verify_blob_kzg_proof_batch_case_0f3f1d3f48f71495",
+            include_str!("../tests/verify_blob_kzg_proof_batch/verify_blob_kzg_proof_batch_case_0f3f1d3f48f71495/data.yaml"),
         ),
         (
-            "verify_blob_kzg_proof_case_correct_proof_cdb3e6d49eb12307",
-            include_str!("../tests/verify_blob_kzg_proof/verify_blob_kzg_proof_case_correct_proof_cdb3e6d49eb12307/data.yaml"),
+            "verify_blob_kzg_proof_batch_case_12c097d7ca0261e3",
+            include_str!("../tests/verify_blob_kzg_proof_batch/verify_blob_kzg_proof_batch_case_12c097d7ca0261e3/data.yaml"),
         ),
         (
-            "verify_blob_kzg_proof_case_correct_proof_fb324bc819407148",
-            include_str!("../tests/verify_blob_kzg_proof/verify_blob_kzg_proof_case_correct_proof_fb324bc819407148/data.yaml"),
+            "verify_blob_kzg_proof_batch_case_2ef482373a81e34e",
+            include_str!("../tests/verify_blob_kzg_proof_batch/verify_blob_kzg_proof_batch_case_2ef482373a81e34e/data.yaml"),
         ),
         (
-            "verify_blob_kzg_proof_case_incorrect_proof_0951cfd9ab47a8d3",
-            include_str!(
-                "../tests/verify_blob_kzg_proof/verify_blob_kzg_proof_case_incorrect_proof_0951cfd9ab47a8d3/data.yaml"
-            ),
+            "verify_blob_kzg_proof_batch_case_a271b78b8e869d69",
+            include_str!("../tests/verify_blob_kzg_proof_batch/verify_blob_kzg_proof_batch_case_a271b78b8e869d69/data.yaml"),
         ),
         (
-            "verify_blob_kzg_proof_case_incorrect_proof_19b3f3f8c98ea31e",
-            include_str!(
-                "../tests/verify_blob_kzg_proof/verify_blob_kzg_proof_case_incorrect_proof_19b3f3f8c98ea31e/data.yaml"
-            ),
+            "verify_blob_kzg_proof_batch_case_blob_length_different",
+            include_str!("../tests/verify_blob_kzg_proof_batch/verify_blob_kzg_proof_batch_case_blob_length_different/data.yaml"),
         ),
         (
-            "verify_blob_kzg_proof_case_incorrect_proof_84d8089232bc23a8",
-            include_str!(
-                "../tests/verify_blob_kzg_proof/verify_blob_kzg_proof_case_incorrect_proof_84d8089232bc23a8/data.yaml"
-            ),
+            "verify_blob_kzg_proof_batch_case_cb3c3279a1afddcf",
+            include_str!("../tests/verify_blob_kzg_proof_batch/verify_blob_kzg_proof_batch_case_cb3c3279a1afddcf/data.yaml"),
         ),
         (
-            "verify_blob_kzg_proof_case_incorrect_proof_a87a4e636e0f58fb",
-            include_str!(
-                "../tests/verify_blob_kzg_proof/verify_blob_kzg_proof_case_incorrect_proof_a87a4e636e0f58fb/data.yaml"
-            ),
+            "verify_blob_kzg_proof_batch_case_commitment_length_different",
+            include_str!("../tests/verify_blob_kzg_proof_batch/verify_blob_kzg_proof_batch_case_commitment_length_different/data.yaml"),
         ),
         (
-            "verify_blob_kzg_proof_case_incorrect_proof_c40b9b515df8721b",
-            include_str!(
-                "../tests/verify_blob_kzg_proof/verify_blob_kzg_proof_case_incorrect_proof_c40b9b515df8721b/data.yaml"
-            ),
+            "verify_blob_kzg_proof_batch_case_e61aafba051ddf79",
+            include_str!("../tests/verify_blob_kzg_proof_batch/verify_blob_kzg_proof_batch_case_e61aafba051ddf79/data.yaml"),
         ),
         (
-            "verify_blob_kzg_proof_case_incorrect_proof_cdb3e6d49eb12307",
-            include_str!(
-                "../tests/verify_blob_kzg_proof/verify_blob_kzg_proof_case_incorrect_proof_cdb3e6d49eb12307/data.yaml"
-            ),
+            "verify_blob_kzg_proof_batch_case_incorrect_proof_add_one",
+            include_str!("../tests/verify_blob_kzg_proof_batch/verify_blob_kzg_proof_batch_case_incorrect_proof_add_one/data.yaml"),
         ),
         (
-            "verify_blob_kzg_proof_case_incorrect_proof_fb324bc819407148",
-            include_str!(
-                "../tests/verify_blob_kzg_proof/verify_blob_kzg_proof_case_incorrect_proof_fb324bc819407148/data.yaml"
-            ),
+            "verify_blob_kzg_proof_batch_case_incorrect_proof_point_at_infinity",
+            include_str!("../tests/verify_blob_kzg_proof_batch/verify_blob_kzg_proof_batch_case_incorrect_proof_point_at_infinity/data.yaml"),
         ),
         (
-            "verify_blob_kzg_proof_case_incorrect_proof_point_at_infinity",
-            include_str!(
-                "../tests/verify_blob_kzg_proof/verify_blob_kzg_proof_case_incorrect_proof_point_at_infinity/data.yaml"
-            ),
+            "verify_blob_kzg_proof_batch_case_invalid_blob_59d64ff6b4648fad",
+            include_str!("../tests/verify_blob_kzg_proof_batch/verify_blob_kzg_proof_batch_case_invalid_blob_59d64ff6b4648fad/data.yaml"),
         ),
         (
-            "verify_blob_kzg_proof_case_invalid_blob_59d64ff6b4648fad",
-            include_str!("../tests/verify_blob_kzg_proof/verify_blob_kzg_proof_case_invalid_blob_59d64ff6b4648fad/data.yaml"),
+            "verify_blob_kzg_proof_batch_case_invalid_blob_635fb2de5b0dc429",
+            include_str!("../tests/verify_blob_kzg_proof_batch/verify_blob_kzg_proof_batch_case_invalid_blob_635fb2de5b0dc429/data.yaml"),
         ),
         (
-            "verify_blob_kzg_proof_case_invalid_blob_635fb2de5b0dc429",
-            include_str!("../tests/verify_blob_kzg_proof/verify_blob_kzg_proof_case_invalid_blob_635fb2de5b0dc429/data.yaml"),
+            "verify_blob_kzg_proof_batch_case_invalid_blob_a3b9ff28507767f8",
+            include_str!("../tests/verify_blob_kzg_proof_batch/verify_blob_kzg_proof_batch_case_invalid_blob_a3b9ff28507767f8/data.yaml"),
         ),
         (
-            "verify_blob_kzg_proof_case_invalid_blob_a3b9ff28507767f8",
-            include_str!("../tests/verify_blob_kzg_proof/verify_blob_kzg_proof_case_invalid_blob_a3b9ff28507767f8/data.yaml"),
+            "verify_blob_kzg_proof_batch_case_invalid_blob_d3afbd98123a3434",
+            include_str!("../tests/verify_blob_kzg_proof_batch/verify_blob_kzg_proof_batch_case_invalid_blob_d3afbd98123a3434/data.yaml"),
         ),
         (
-            "verify_blob_kzg_proof_case_invalid_blob_d3afbd98123a3434",
-            include_str!("../tests/verify_blob_kzg_proof/verify_blob_kzg_proof_case_invalid_blob_d3afbd98123a3434/data.yaml"),
+            "verify_blob_kzg_proof_batch_case_invalid_commitment_1a68c47b68148e78",
+            include_str!("../tests/verify_blob_kzg_proof_batch/verify_blob_kzg_proof_batch_case_invalid_commitment_1a68c47b68148e78/data.yaml"),
         ),
         (
-            "verify_blob_kzg_proof_case_invalid_commitment_1a68c47b68148e78",
-            include_str!(
-                "../tests/verify_blob_kzg_proof/verify_blob_kzg_proof_case_invalid_commitment_1a68c47b68148e78/data.yaml"
-            ),
+            "verify_blob_kzg_proof_batch_case_invalid_commitment_24b932fb4dec5b2d",
+            include_str!("../tests/verify_blob_kzg_proof_batch/verify_blob_kzg_proof_batch_case_invalid_commitment_24b932fb4dec5b2d/data.yaml"),
         ),
         (
-            "verify_blob_kzg_proof_case_invalid_commitment_24b932fb4dec5b2d",
-            include_str!(
-                "../tests/verify_blob_kzg_proof/verify_blob_kzg_proof_case_invalid_commitment_24b932fb4dec5b2d/data.yaml"
-            ),
+            "verify_blob_kzg_proof_batch_case_invalid_commitment_3a6eb616efae0627",
+            include_str!("../tests/verify_blob_kzg_proof_batch/verify_blob_kzg_proof_batch_case_invalid_commitment_3a6eb616efae0627/data.yaml"),
         ),
         (
-            "verify_blob_kzg_proof_case_invalid_commitment_3a6eb616efae0627",
-            include_str!(
-                "../tests/verify_blob_kzg_proof/verify_blob_kzg_proof_case_invalid_commitment_3a6eb616efae0627/data.yaml"
-            ),
+            "verify_blob_kzg_proof_batch_case_invalid_commitment_d070689c3e15444c",
+            include_str!("../tests/verify_blob_kzg_proof_batch/verify_blob_kzg_proof_batch_case_invalid_commitment_d070689c3e15444c/data.yaml"),
         ),
         (
-            "verify_blob_kzg_proof_case_invalid_commitment_d070689c3e15444c",
-            include_str!(
-                "../tests/verify_blob_kzg_proof/verify_blob_kzg_proof_case_invalid_commitment_d070689c3e15444c/data.yaml"
-            ),
+            "verify_blob_kzg_proof_batch_case_invalid_proof_1a68c47b68148e78",
+            include_str!("../tests/verify_blob_kzg_proof_batch/verify_blob_kzg_proof_batch_case_invalid_proof_1a68c47b68148e78/data.yaml"),
         ),
         (
-            "verify_blob_kzg_proof_case_invalid_proof_1a68c47b68148e78",
-            include_str!("../tests/verify_blob_kzg_proof/verify_blob_kzg_proof_case_invalid_proof_1a68c47b68148e78/data.yaml"),
+            "verify_blob_kzg_proof_batch_case_invalid_proof_24b932fb4dec5b2d",
+            include_str!("../tests/verify_blob_kzg_proof_batch/verify_blob_kzg_proof_batch_case_invalid_proof_24b932fb4dec5b2d/data.yaml"),
         ),
         (
-            "verify_blob_kzg_proof_case_invalid_proof_24b932fb4dec5b2d",
-            include_str!("../tests/verify_blob_kzg_proof/verify_blob_kzg_proof_case_invalid_proof_24b932fb4dec5b2d/data.yaml"),
+            "verify_blob_kzg_proof_batch_case_invalid_proof_3a6eb616efae0627",
+            include_str!("../tests/verify_blob_kzg_proof_batch/verify_blob_kzg_proof_batch_case_invalid_proof_3a6eb616efae0627/data.yaml"),
         ),
         (
-            "verify_blob_kzg_proof_case_invalid_proof_3a6eb616efae0627",
-            include_str!("../tests/verify_blob_kzg_proof/verify_blob_kzg_proof_case_invalid_proof_3a6eb616efae0627/data.yaml"),
+            "verify_blob_kzg_proof_batch_case_invalid_proof_d070689c3e15444c",
+            include_str!("../tests/verify_blob_kzg_proof_batch/verify_blob_kzg_proof_batch_case_invalid_proof_d070689c3e15444c/data.yaml"),
         ),
         (
-            "verify_blob_kzg_proof_case_invalid_proof_d070689c3e15444c",
-            include_str!("../tests/verify_blob_kzg_proof/verify_blob_kzg_proof_case_invalid_proof_d070689c3e15444c/data.yaml"),
+            "verify_blob_kzg_proof_batch_case_proof_length_different",
+            include_str!("../tests/verify_blob_kzg_proof_batch/verify_blob_kzg_proof_batch_case_proof_length_different/data.yaml"),
         ),
     ];
 
@@ -0,0 +1,177 @@
+//! Multi-scalar multiplication over G1 with the bucket method of Pippenger, which replaces most
+//! of the doublings of one scalar multiplication per term with cheap bucket additions.
+
+use alloc::vec::Vec;
+use bls12_381::{G1Affine, G1Projective, Scalar};
+use ff::PrimeField;
+
+/// Below this many terms, filling the buckets costs more than it saves
+const NAIVE_THRESHOLD: usize = 8;
+
+/// Computes the sum of `scalars[i] * points[i]`. As with `msm_variable_base`, terms past the end
+/// of the shorter slice are ignored.
+pub fn g1_lincomb(points: &[G1Affine], scalars: &[Scalar]) -> G1Projective {
+    g1_lincomb_with_window(points, scalars, None)
+}
+
+/// [`g1_lincomb`] with buckets `window` bits wide, clamped to `1..=16`, instead of a width picked
+/// from the number of terms
+pub fn g1_lincomb_with_window(
+    points: &[G1Affine],
+    scalars: &[Scalar],
+    window: Option<u8>,
+) -> G1Projective {
+    let n = points.len().min(scalars.len());
+    if n < NAIVE_THRESHOLD {
+        return points
+            .iter()
+            .zip(scalars)
+            .fold(G1Projective::identity(), |acc, (point, scalar)| {
+                acc + point * scalar
+            });
+    }
+
+    let window = window.map_or_else(|| window_bits(n), |bits| usize::from(bits.clamp(1, 16)));
+    let scalars = scalars[..n]
+        .iter()
+        .map(Scalar::to_bytes)
+        .collect::<Vec<_>>();
+    let mut buckets = vec![G1Projective::identity(); (1 << window) - 1];
+
+    // Horner over the windows, from the most significant one down
+    let mut result = G1Projective::identity();
+    for start in (0..Scalar::NUM_BITS as usize).step_by(window).rev() {
+        for _ in 0..window {
+            result = result.double();
+        }
+
+        buckets.fill(G1Projective::identity());
+        for (point, scalar) in points.iter().zip(&scalars) {
+            let digit = window_digit(scalar, start, window);
+            if digit != 0 {
+                buckets[digit - 1] += point;
+            }
+        }
+
+        // sum_d d * B_d, as the sum of the running sums from the top bucket down
+        let mut running = G1Projective::identity();
+        let mut window_sum = G1Projective::identity();
+        for bucket in buckets.iter().rev() {
+            running += bucket;
+            window_sum += running;
+        }
+        result += window_sum;
+    }
+
+    result
+}
+
+/// Roughly `ln(n) + 2` bits, which balances the additions into the buckets against the
+/// additions summing them
+fn window_bits(n: usize) -> usize {
+    (n.ilog2() as usize * 2 / 3 + 2).min(16)
+}
+
+/// Bits `start..start + window` of a little-endian scalar
+fn window_digit(bytes: &[u8; 32], start: usize, window: usize) -> usize {
+    (start..(start + window).min(8 * bytes.len()))
+        .rev()
+        .fold(0, |digit, bit| {
+            (digit << 1) | ((bytes[bit / 8] >> (bit % 8)) & 1) as usize
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::trusted_setup::KzgSettings;
+    use sha2::{Digest, Sha256};
+
+    fn pseudo_random_scalar(seed: u64) -> Scalar {
+        let mut wide = [0u8; 64];
+        wide[..32].copy_from_slice(&Sha256::digest(seed.to_le_bytes()));
+        wide[32..].copy_from_slice(&Sha256::digest((!seed).to_le_bytes()));
+        Scalar::from_bytes_wide(&wide)
+    }
+
+    fn naive_lincomb(points: &[G1Affine], scalars: &[Scalar]) -> G1Projective {
+        points
+            .iter()
+            .zip(scalars)
+            .fold(G1Projective::identity(), |acc, (point, scalar)| {
+                acc + point * scalar
+            })
+    }
+
+    #[test]
+    fn test_g1_lincomb_matches_naive() {
+        for n in [0, 1, 7, 8, 9, 100, 300] {
+            let points = (0..n)
+                .map(|i| (G1Affine::generator() * pseudo_random_scalar(i)).into())
+                .collect::<Vec<G1Affine>>();
+            let mut scalars = (0..n)
+                .map(|i| pseudo_random_scalar(i + 1000))
+                .collect::<Vec<_>>();
+            // The extremes fill the top and the empty bucket of every window
+            if n > 2 {
+                scalars[0] = -Scalar::one();
+                scalars[1] = Scalar::zero();
+                scalars[2] = Scalar::one();
+            }
+
+            assert_eq!(
+                g1_lincomb(&points, &scalars),
+                naive_lincomb(&points, &scalars)
+            );
+        }
+    }
+
+    #[test]
+    fn test_g1_lincomb_with_window() {
+        let points = (0..50)
+            .map(|i| (G1Affine::generator() * pseudo_random_scalar(i)).into())
+            .collect::<Vec<G1Affine>>();
+        let scalars = (0..50)
+            .map(|i| pseudo_random_scalar(i + 1000))
+            .collect::<Vec<_>>();
+        let expected = naive_lincomb(&points, &scalars);
+
+        // Including widths that do not divide the scalar size, and out of range ones
+        for window in [0, 1, 5, 8, 13, 16, 255] {
+            assert_eq!(
+                g1_lincomb_with_window(&points, &scalars, Some(window)),
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn test_g1_lincomb_with_identity_points() {
+        let points = vec![G1Affine::identity(); 20];
+        let scalars = (0..20).map(pseudo_random_scalar).collect::<Vec<_>>();
+        assert_eq!(g1_lincomb(&points, &scalars), G1Projective::identity());
+    }
+
+    #[test]
+    #[ignore = "benchmark, run with `cargo test --release -- --ignored --nocapture`"]
+    fn bench_g1_lincomb_vs_scalar_muls() {
+        use std::time::Instant;
+
+        let kzg_settings = KzgSettings::load_trusted_setup_file().unwrap();
+        let scalars = (0..kzg_settings.g1_points.len() as u64)
+            .map(pseudo_random_scalar)
+            .collect::<Vec<_>>();
+
+        let start = Instant::now();
+        let pippenger = g1_lincomb(kzg_settings.g1_points, &scalars);
+        let bucketed = start.elapsed();
+
+        let start = Instant::now();
+        let naive = naive_lincomb(kzg_settings.g1_points, &scalars);
+        let sequential = start.elapsed();
+
+        println!("pippenger: {bucketed:?}, scalar muls: {sequential:?}");
+        assert_eq!(pippenger, naive);
+        assert!(bucketed < sequential);
+    }
+}
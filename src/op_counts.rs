@@ -0,0 +1,56 @@
+//! Counts of the expensive operations run on the current thread, for profiling. Only compiled
+//! with the `op-counts` feature, which pulls in `std` for the thread-local counter.
+
+use core::cell::Cell;
+
+/// Expensive operations performed while running a closure through [`measure`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct OpCounts {
+    /// Standalone `pairing` calls, each with its own Miller loop and final exponentiation
+    pub pairings: usize,
+    /// `multi_miller_loop` calls, however many pairs each one covers
+    pub multi_miller_loops: usize,
+    pub final_exponentiations: usize,
+    /// G1 scalar multiplications, with an MSM of `n` terms counted as `n`
+    pub g1_scalar_muls: usize,
+    pub field_inversions: usize,
+}
+
+std::thread_local! {
+    static COUNTS: Cell<OpCounts> = const { Cell::new(OpCounts {
+        pairings: 0,
+        multi_miller_loops: 0,
+        final_exponentiations: 0,
+        g1_scalar_muls: 0,
+        field_inversions: 0,
+    }) };
+}
+
+/// Runs `f` and returns its result along with the operations it performed on this thread.
+/// Work spread over other threads, e.g. with the `parallel` feature, is not counted.
+pub fn measure<T>(f: impl FnOnce() -> T) -> (T, OpCounts) {
+    let outer = COUNTS.with(|counts| counts.replace(OpCounts::default()));
+    let result = f();
+    let inner = COUNTS.with(|counts| counts.get());
+
+    // Keep the counts of an enclosing `measure` call intact
+    COUNTS.with(|counts| {
+        counts.set(OpCounts {
+            pairings: outer.pairings + inner.pairings,
+            multi_miller_loops: outer.multi_miller_loops + inner.multi_miller_loops,
+            final_exponentiations: outer.final_exponentiations + inner.final_exponentiations,
+            g1_scalar_muls: outer.g1_scalar_muls + inner.g1_scalar_muls,
+            field_inversions: outer.field_inversions + inner.field_inversions,
+        })
+    });
+
+    (result, inner)
+}
+
+pub(crate) fn record(update: impl FnOnce(&mut OpCounts)) {
+    COUNTS.with(|counts| {
+        let mut current = counts.get();
+        update(&mut current);
+        counts.set(current);
+    });
+}
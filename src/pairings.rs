@@ -1,9 +1,106 @@
 #[allow(unused_imports)]
-use bls12_381::{multi_miller_loop, G1Affine, G2Affine, G2Prepared, Gt, Scalar};
+use alloc::{borrow::Cow, vec::Vec};
+#[allow(unused_imports)]
+use bls12_381::{multi_miller_loop, G1Affine, G2Affine, G2Prepared, Gt, MillerLoopResult, Scalar};
+use subtle::{Choice, ConstantTimeEq};
+
+/// Checks that a product of pairings `e(a_1, b_1) * ... * e(a_n, b_n)` is the identity, with one
+/// `multi_miller_loop` and one final exponentiation however many terms there are. A term moves
+/// to the other side of an equation by negating its G1 point, so `e(A, B) == e(C, D)` is
+/// `PairingCheck::<2>::new().add_term(A, B).add_term(-C, D).verify()`.
+///
+/// The terms are held on the stack, up to `N` of them. G2 points prepared ahead of time, e.g.
+/// those of a setup, are borrowed with [`Self::add_prepared_term`], so such a check allocates
+/// nothing.
+#[derive(Debug, Clone)]
+pub struct PairingCheck<'a, const N: usize> {
+    terms: [Option<(G1Affine, Cow<'a, G2Prepared>)>; N],
+    len: usize,
+}
+
+impl<const N: usize> Default for PairingCheck<'_, N> {
+    fn default() -> Self {
+        Self {
+            terms: core::array::from_fn(|_| None),
+            len: 0,
+        }
+    }
+}
+
+impl<'a, const N: usize> PairingCheck<'a, N> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Multiplies `e(g1, g2)` into the product, preparing `g2`. Panics past `N` terms.
+    pub fn add_term(self, g1: G1Affine, g2: G2Affine) -> Self {
+        self.push(g1, Cow::Owned(G2Prepared::from(g2)))
+    }
+
+    /// Same as [`Self::add_term`] with a G2 point the caller prepared
+    pub fn add_prepared_term(self, g1: G1Affine, g2: &'a G2Prepared) -> Self {
+        self.push(g1, Cow::Borrowed(g2))
+    }
+
+    fn push(mut self, g1: G1Affine, g2: Cow<'a, G2Prepared>) -> Self {
+        assert!(
+            self.len < N,
+            "a PairingCheck<{}> holds at most {} terms",
+            N,
+            N
+        );
+        self.terms[self.len] = Some((g1, g2));
+        self.len += 1;
+        self
+    }
+
+    /// The Miller loop over all the terms, before the final exponentiation, to be folded into a
+    /// larger check
+    pub fn miller_loop(&self) -> MillerLoopResult {
+        let Some(Some((first_g1, first_g2))) = self.terms.first() else {
+            return multi_miller_loop(&[]);
+        };
+        // The slots past `len` repeat the first term, and are cut off before the loop
+        let terms: [(&G1Affine, &G2Prepared); N] = core::array::from_fn(|i| match &self.terms[i] {
+            Some((g1, g2)) => (g1, &**g2),
+            None => (first_g1, &**first_g2),
+        });
+        multi_miller_loop(&terms[..self.len])
+    }
+
+    /// Same as [`Self::verify`], but returns the result as a [`Choice`] for constant-time callers
+    pub fn verify_ct(&self) -> Choice {
+        self.miller_loop()
+            .final_exponentiation()
+            .ct_eq(&Gt::identity())
+    }
+
+    /// Whether the product of the pairings is the identity. An empty check trivially holds.
+    pub fn verify(&self) -> bool {
+        self.verify_ct().into()
+    }
+}
 
 /// Verifies the pairing of two G1 and two G2 points are equivalent using the multi-miller loop
 pub fn pairings_verify(a1: G1Affine, a2: G2Affine, b1: G1Affine, b2: G2Affine) -> bool {
-    multi_miller_loop(&[(&-a1, &G2Prepared::from(a2)), (&b1, &G2Prepared::from(b2))])
-        .final_exponentiation()
-        == Gt::identity()
+    pairings_verify_ct(a1, a2, b1, b2).into()
+}
+
+/// Same as [`pairings_verify`], but returns the result as a [`Choice`] for constant-time callers
+pub fn pairings_verify_ct(a1: G1Affine, a2: G2Affine, b1: G1Affine, b2: G2Affine) -> Choice {
+    pairings_verify_prepared_ct(a1, &G2Prepared::from(a2), b1, &G2Prepared::from(b2))
+}
+
+/// Same as [`pairings_verify_ct`] on G2 points the caller prepared, e.g. once for every check
+/// against a fixed setup, so the check itself allocates nothing
+pub fn pairings_verify_prepared_ct(
+    a1: G1Affine,
+    a2: &G2Prepared,
+    b1: G1Affine,
+    b2: &G2Prepared,
+) -> Choice {
+    PairingCheck::<2>::new()
+        .add_prepared_term(-a1, a2)
+        .add_prepared_term(b1, b2)
+        .verify_ct()
 }
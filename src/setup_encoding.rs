@@ -0,0 +1,64 @@
+// Shared with build.rs through `include!`, so only fully qualified paths are used here
+
+const BYTES_PER_G2_POINT_COMPRESSED: usize = 96;
+const BYTES_PER_G2_POINT_UNCOMPRESSED: usize = 192;
+
+/// Decodes a G2 point of a trusted setup file, accepting both the compressed (96 bytes) and the
+/// uncompressed (192 bytes) encodings so setups can mix them line by line. Like the rest of the
+/// setup decoding, the point is not subgroup checked.
+pub fn g2_from_setup_bytes(bytes: &[u8]) -> Option<bls12_381::G2Affine> {
+    match bytes.len() {
+        BYTES_PER_G2_POINT_COMPRESSED => {
+            let mut compressed = [0u8; BYTES_PER_G2_POINT_COMPRESSED];
+            compressed.copy_from_slice(bytes);
+            bls12_381::G2Affine::from_compressed_unchecked(&compressed).into()
+        }
+        BYTES_PER_G2_POINT_UNCOMPRESSED => {
+            let mut uncompressed = [0u8; BYTES_PER_G2_POINT_UNCOMPRESSED];
+            uncompressed.copy_from_slice(bytes);
+            bls12_381::G2Affine::from_uncompressed_unchecked(&uncompressed).into()
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::trusted_setup::get_g2_points;
+
+    #[test]
+    fn test_g2_from_setup_bytes_mixed_encodings() {
+        let trusted_setup = include_str!("trusted_setup.txt");
+        let lines = trusted_setup.lines().collect::<Vec<_>>();
+        let num_g1_points = lines[0].parse::<usize>().unwrap();
+        let num_g2_points = lines[1].parse::<usize>().unwrap();
+        let g2_lines = &lines[2 + num_g1_points..2 + num_g1_points + num_g2_points];
+
+        // Re-encode every other line uncompressed, as some setup files do
+        let mixed = g2_lines
+            .iter()
+            .enumerate()
+            .map(|(i, line)| {
+                let bytes = hex::decode(line).unwrap();
+                if i % 2 == 0 {
+                    return bytes;
+                }
+                let point = g2_from_setup_bytes(&bytes).unwrap();
+                point.to_uncompressed().to_vec()
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(mixed[1].len(), BYTES_PER_G2_POINT_UNCOMPRESSED);
+
+        let decoded = mixed
+            .iter()
+            .map(|bytes| g2_from_setup_bytes(bytes))
+            .collect::<Option<Vec<_>>>()
+            .unwrap();
+        assert_eq!(decoded, get_g2_points());
+
+        for len in [0, 48, 95, 97, 191, 193] {
+            assert!(g2_from_setup_bytes(&vec![0u8; len]).is_none());
+        }
+    }
+}
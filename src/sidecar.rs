@@ -0,0 +1,175 @@
+use crate::{
+    dtypes::{Blob, Bytes48},
+    enums::KzgError,
+    kzg_proof::KzgProof,
+    trusted_setup::KzgSettings,
+};
+
+use alloc::vec::Vec;
+
+/// The blobs of a transaction together with their commitments and proofs, entry `i` of each
+/// vector belonging together
+#[derive(Debug, Clone)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "SidecarParts")
+)]
+pub struct BlobSidecar {
+    blobs: Vec<Blob>,
+    commitments: Vec<Bytes48>,
+    proofs: Vec<Bytes48>,
+}
+
+/// Unvalidated [`BlobSidecar`], so deserialization goes through [`BlobSidecar::new`]
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct SidecarParts {
+    blobs: Vec<Blob>,
+    commitments: Vec<Bytes48>,
+    proofs: Vec<Bytes48>,
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<SidecarParts> for BlobSidecar {
+    type Error = KzgError;
+
+    fn try_from(parts: SidecarParts) -> Result<Self, Self::Error> {
+        Self::new(parts.blobs, parts.commitments, parts.proofs)
+    }
+}
+
+impl BlobSidecar {
+    /// Errors unless there is exactly one commitment and one proof per blob
+    pub fn new(
+        blobs: Vec<Blob>,
+        commitments: Vec<Bytes48>,
+        proofs: Vec<Bytes48>,
+    ) -> Result<Self, KzgError> {
+        if blobs.len() != commitments.len() {
+            return Err(KzgError::MismatchedLengths {
+                expected: blobs.len(),
+                got: commitments.len(),
+            });
+        }
+
+        if blobs.len() != proofs.len() {
+            return Err(KzgError::MismatchedLengths {
+                expected: blobs.len(),
+                got: proofs.len(),
+            });
+        }
+
+        Ok(Self {
+            blobs,
+            commitments,
+            proofs,
+        })
+    }
+
+    pub fn blobs(&self) -> &[Blob] {
+        &self.blobs
+    }
+
+    pub fn commitments(&self) -> &[Bytes48] {
+        &self.commitments
+    }
+
+    pub fn proofs(&self) -> &[Bytes48] {
+        &self.proofs
+    }
+
+    pub fn len(&self) -> usize {
+        self.blobs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.blobs.is_empty()
+    }
+
+    /// Verifies every blob proof as [`KzgProof::verify_blob_kzg_proof_batch`] does, without
+    /// copying the blobs
+    pub fn verify(&self, kzg_settings: &KzgSettings) -> Result<bool, KzgError> {
+        KzgProof::verify_blob_kzg_proof_batch_with_stats(
+            &self.blobs,
+            &self.commitments,
+            &self.proofs,
+            kzg_settings,
+        )
+        .map(|(valid, _)| valid)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kzg_proof::{blob_to_kzg_commitment, compute_blob_kzg_proof};
+
+    fn sidecar(kzg_settings: &KzgSettings) -> BlobSidecar {
+        let mut blob_bytes = vec![0u8; crate::BYTES_PER_BLOB];
+        for (i, chunk) in blob_bytes.chunks_exact_mut(32).enumerate() {
+            chunk[31] = i as u8;
+        }
+        let blobs = vec![Blob::zero(), Blob::from_slice(&blob_bytes).unwrap()];
+        let commitments = blobs
+            .iter()
+            .map(|blob| blob_to_kzg_commitment(blob, kzg_settings).unwrap())
+            .collect::<Vec<_>>();
+        let proofs = blobs
+            .iter()
+            .zip(&commitments)
+            .map(|(blob, commitment)| {
+                compute_blob_kzg_proof(blob, commitment, kzg_settings).unwrap()
+            })
+            .collect::<Vec<_>>();
+        BlobSidecar::new(blobs, commitments, proofs).unwrap()
+    }
+
+    #[test]
+    fn test_blob_sidecar_verify() {
+        let kzg_settings = KzgSettings::load_trusted_setup_file().unwrap();
+        let sidecar = sidecar(&kzg_settings);
+        assert_eq!(sidecar.len(), 2);
+        assert!(sidecar.verify(&kzg_settings).unwrap());
+
+        let mut proofs = sidecar.proofs().to_vec();
+        proofs.swap(0, 1);
+        let swapped = BlobSidecar::new(
+            sidecar.blobs().to_vec(),
+            sidecar.commitments().to_vec(),
+            proofs,
+        )
+        .unwrap();
+        assert!(!swapped.verify(&kzg_settings).unwrap());
+
+        assert!(BlobSidecar::new(vec![Blob::zero()], vec![], vec![]).is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_blob_sidecar_serde_roundtrip() {
+        let kzg_settings = KzgSettings::load_trusted_setup_file().unwrap();
+        let sidecar = sidecar(&kzg_settings);
+
+        let yaml = serde_yaml::to_string(&sidecar).unwrap();
+        assert!(yaml.contains(&format!(
+            "0x{}",
+            hex::encode(sidecar.commitments()[1].as_slice())
+        )));
+        let decoded: BlobSidecar = serde_yaml::from_str(&yaml).unwrap();
+        assert_eq!(decoded.blobs()[1].as_slice(), sidecar.blobs()[1].as_slice());
+        assert_eq!(
+            decoded.commitments()[1].as_slice(),
+            sidecar.commitments()[1].as_slice()
+        );
+        assert_eq!(
+            decoded.proofs()[0].as_slice(),
+            sidecar.proofs()[0].as_slice()
+        );
+        assert!(decoded.verify(&kzg_settings).unwrap());
+
+        // Mismatched lengths are rejected while deserializing
+        let truncated = yaml.replacen("proofs:\n- ", "proofs:\n# ", 1);
+        assert!(serde_yaml::from_str::<BlobSidecar>(&truncated).is_err());
+    }
+}
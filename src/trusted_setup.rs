@@ -1,54 +1,388 @@
-use crate::{enums::KzgError, NUM_G1_POINTS, NUM_ROOTS_OF_UNITY};
-
-use alloc::sync::Arc;
-use bls12_381::{G1Affine, G2Affine, Scalar};
-use core::{
-    hash::{Hash, Hasher},
-    mem::transmute,
-    slice,
+use crate::{
+    dtypes::{Bytes32, Endianness, SubgroupPolicy},
+    enums::KzgError,
+    kzg_proof::scalar_to_blob_bytes,
+    msm::g1_lincomb_with_window,
+    pairings_verify,
+    setup_encoding::g2_from_setup_bytes,
+    BYTES_PER_G1_POINT, BYTES_PER_G2_POINT, DOMAIN_STR_LENGTH, FIAT_SHAMIR_PROTOCOL_DOMAIN,
+    NUM_G1_POINTS, NUM_G2_POINTS, NUM_ROOTS_OF_UNITY, RANDOM_CHALLENGE_KZG_BATCH_DOMAIN,
+    SCALE2_ROOT_OF_UNITY,
 };
+
+use alloc::{borrow::Cow, string::ToString, sync::Arc, vec::Vec};
+use bls12_381::{G1Affine, G1Projective, G2Affine, G2Prepared, Scalar};
+use core::hash::{Hash, Hasher};
+#[cfg(feature = "unsafe-opt")]
+use core::{mem::transmute, slice};
 use spin::Once;
+use subtle::Choice;
+
+/// [`FIAT_SHAMIR_PROTOCOL_DOMAIN`] as stored in [`KzgSettings::fiat_shamir_domain`]
+pub(crate) const DEFAULT_FIAT_SHAMIR_DOMAIN: [u8; DOMAIN_STR_LENGTH] =
+    domain_bytes(FIAT_SHAMIR_PROTOCOL_DOMAIN);
+/// [`RANDOM_CHALLENGE_KZG_BATCH_DOMAIN`] as stored in [`KzgSettings::batch_domain`]
+pub(crate) const DEFAULT_BATCH_DOMAIN: [u8; DOMAIN_STR_LENGTH] =
+    domain_bytes(RANDOM_CHALLENGE_KZG_BATCH_DOMAIN);
+
+const fn domain_bytes(domain: &str) -> [u8; DOMAIN_STR_LENGTH] {
+    let mut bytes = [0; DOMAIN_STR_LENGTH];
+    let mut i = 0;
+    while i < DOMAIN_STR_LENGTH {
+        bytes[i] = domain.as_bytes()[i];
+        i += 1;
+    }
+    bytes
+}
+
+/// Estimated work of verifying a batch of blob proofs, see [`KzgSettings::estimate_verify_cost`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct VerifyCost {
+    /// Pairs fed to the Miller loop, two for any non-empty batch
+    pub pairings: usize,
+    /// G1 scalar multiplications, with an MSM of `n` terms counted as `n`
+    pub g1_scalar_muls: usize,
+    /// Scalar field multiplications and inversions
+    pub field_ops: usize,
+}
+
+/// Aligns the embedded setup bytes for the points viewed in place by the `unsafe-opt` getters
+#[cfg(feature = "unsafe-opt")]
+#[repr(C, align(8))]
+struct Aligned<Bytes: ?Sized>(Bytes);
 
+/// Views the in-memory points written by `build.rs` in place, without decoding them
+#[cfg(feature = "unsafe-opt")]
 pub fn get_roots_of_unity() -> &'static [Scalar] {
-    static ROOTS_OF_UNITY: Once<&'static [Scalar]> = Once::new();
+    static BYTES: &Aligned<[u8]> = &Aligned(*include_bytes!(concat!(
+        env!("OUT_DIR"),
+        "/roots_of_unity.bin"
+    )));
+    unsafe { transmute(slice::from_raw_parts(BYTES.0.as_ptr(), NUM_ROOTS_OF_UNITY)) }
+}
+
+#[cfg(feature = "unsafe-opt")]
+pub fn get_g1_points() -> &'static [G1Affine] {
+    static BYTES: &Aligned<[u8]> = &Aligned(*include_bytes!(concat!(env!("OUT_DIR"), "/g1.bin")));
+    unsafe { transmute(slice::from_raw_parts(BYTES.0.as_ptr(), NUM_G1_POINTS)) }
+}
+
+#[cfg(feature = "unsafe-opt")]
+pub fn get_g2_points() -> &'static [G2Affine] {
+    static BYTES: &Aligned<[u8]> = &Aligned(*include_bytes!(concat!(env!("OUT_DIR"), "/g2.bin")));
+    unsafe { transmute(slice::from_raw_parts(BYTES.0.as_ptr(), NUM_G2_POINTS)) }
+}
+
+/// Decodes the canonical encodings written by `build.rs` once, on first access
+#[cfg(not(feature = "unsafe-opt"))]
+pub fn get_roots_of_unity() -> &'static [Scalar] {
+    static ROOTS_OF_UNITY: Once<Vec<Scalar>> = Once::new();
     ROOTS_OF_UNITY.call_once(|| {
-        let bytes = include_bytes!(concat!(env!("OUT_DIR"), "/roots_of_unity.bin"));
-        unsafe { transmute(slice::from_raw_parts(bytes.as_ptr(), NUM_ROOTS_OF_UNITY)) }
+        include_bytes!(concat!(env!("OUT_DIR"), "/roots_of_unity_le.bin"))
+            .chunks_exact(32)
+            .map(|bytes| Scalar::from_bytes(bytes.try_into().unwrap()).unwrap())
+            .collect()
     })
 }
 
+#[cfg(not(feature = "unsafe-opt"))]
 pub fn get_g1_points() -> &'static [G1Affine] {
-    static G1_POINTS: Once<&'static [G1Affine]> = Once::new();
+    static G1_POINTS: Once<Vec<G1Affine>> = Once::new();
     G1_POINTS.call_once(|| {
-        let bytes = include_bytes!(concat!(env!("OUT_DIR"), "/g1.bin"));
-        unsafe { transmute(slice::from_raw_parts(bytes.as_ptr(), NUM_G1_POINTS)) }
+        include_bytes!(concat!(env!("OUT_DIR"), "/g1_uncompressed.bin"))
+            .chunks_exact(2 * BYTES_PER_G1_POINT)
+            .map(|bytes| G1Affine::from_uncompressed_unchecked(bytes.try_into().unwrap()).unwrap())
+            .collect()
     })
 }
 
+#[cfg(not(feature = "unsafe-opt"))]
 pub fn get_g2_points() -> &'static [G2Affine] {
-    static G2_POINTS: Once<&'static [G2Affine]> = Once::new();
+    static G2_POINTS: Once<Vec<G2Affine>> = Once::new();
     G2_POINTS.call_once(|| {
-        let bytes = include_bytes!(concat!(env!("OUT_DIR"), "/g2.bin"));
-        unsafe { transmute(slice::from_raw_parts(bytes.as_ptr(), NUM_G1_POINTS)) }
+        include_bytes!(concat!(env!("OUT_DIR"), "/g2_uncompressed.bin"))
+            .chunks_exact(2 * BYTES_PER_G2_POINT)
+            .map(|bytes| G2Affine::from_uncompressed_unchecked(bytes.try_into().unwrap()).unwrap())
+            .collect()
     })
 }
 
+/// The G2 generator prepared for the Miller loop, shared by every setup
+pub(crate) fn prepared_g2_generator() -> &'static G2Prepared {
+    static PREPARED: Once<G2Prepared> = Once::new();
+    PREPARED.call_once(|| G2Prepared::from(G2Affine::generator()))
+}
+
+/// `[tau]G2` prepared for the Miller loop, see [`KzgSettings::prepared_tau_g2`]
+#[derive(Debug)]
+struct PreparedTauG2 {
+    tau_g2: G2Affine,
+    prepared: G2Prepared,
+}
+
+/// Prepares `[tau]G2`, sharing a single instance between every copy of the built-in setup
+fn prepare_tau_g2(tau_g2: G2Affine) -> Arc<PreparedTauG2> {
+    static BUILT_IN: Once<Arc<PreparedTauG2>> = Once::new();
+    let prepare = |tau_g2| {
+        Arc::new(PreparedTauG2 {
+            tau_g2,
+            prepared: G2Prepared::from(tau_g2),
+        })
+    };
+
+    let built_in = BUILT_IN.call_once(|| prepare(get_g2_points()[1]));
+    if built_in.tau_g2 == tau_g2 {
+        built_in.clone()
+    } else {
+        prepare(tau_g2)
+    }
+}
+
+/// Returns `true` if `root` has multiplicative order exactly `2^log2_order`
+fn is_primitive_root_of_unity(root: &Scalar, log2_order: usize) -> bool {
+    if log2_order == 0 {
+        return *root == Scalar::one();
+    }
+
+    // Square down to root^(2^(log2_order - 1)), which must be -1 for a primitive root
+    let mut half_order_power = *root;
+    for _ in 1..log2_order {
+        half_order_power = half_order_power.square();
+    }
+    half_order_power != Scalar::one() && half_order_power.square() == Scalar::one()
+}
+
+/// Checks that every `SCALE2_ROOT_OF_UNITY[k]` is a canonical primitive `2^k`-th root of unity
+/// of the scalar field, validating the hard-coded table against the modulus.
+pub fn verify_roots_of_unity_table() -> bool {
+    SCALE2_ROOT_OF_UNITY
+        .iter()
+        .enumerate()
+        .all(|(log2_order, limbs)| {
+            let root = Scalar::from_raw(*limbs);
+            // `from_raw` reduces modulo the field, so round-trip to reject non-canonical limbs
+            let canonical = root
+                .to_bytes()
+                .chunks_exact(8)
+                .zip(limbs)
+                .all(|(bytes, limb)| bytes == limb.to_le_bytes());
+
+            canonical && is_primitive_root_of_unity(&root, log2_order)
+        })
+}
+
+/// Returns the primitive `2^log2_size`-th root of unity generating an FFT domain of that size.
+///
+/// # Panics
+///
+/// Panics if `log2_size` exceeds the 2-adicity of the scalar field (31).
+pub fn domain_generator(log2_size: usize) -> Scalar {
+    Scalar::from_raw(SCALE2_ROOT_OF_UNITY[log2_size])
+}
+
+/// The primitive `2^order_log2`-th root of unity of [`SCALE2_ROOT_OF_UNITY`], i.e.
+/// [`domain_generator`] under the name the specs use.
+///
+/// # Panics
+///
+/// Panics if `order_log2` exceeds the 2-adicity of the scalar field (31).
+pub fn primitive_root_of_unity(order_log2: usize) -> Scalar {
+    domain_generator(order_log2)
+}
+
+/// The blob domain in the bit-reversed order of the setup, as a fixed-size array. It is the
+/// table behind [`get_roots_of_unity`], so nothing is expanded again.
+pub fn roots_of_unity() -> &'static [Scalar; NUM_ROOTS_OF_UNITY] {
+    get_roots_of_unity()
+        .try_into()
+        .expect("the built-in domain has NUM_ROOTS_OF_UNITY points")
+}
+
+/// Builds a setup of `size` points from a known secret `tau`.
+///
+/// **INSECURE**: anyone knowing `tau` can forge proofs for this setup. Only meant for fast,
+/// deterministic tests, the points are leaked to get the `'static` slices [`KzgSettings`] holds.
+///
+/// # Panics
+///
+/// Panics if `size` is not a power of two or exceeds the 2-adicity of the scalar field.
+#[cfg(any(test, feature = "testing"))]
+pub fn generate_insecure_setup(tau: Scalar, size: usize) -> KzgSettings {
+    use crate::kzg_proof::compute_powers;
+
+    assert!(
+        size.is_power_of_two(),
+        "The setup size must be a power of two"
+    );
+    let log2_size = size.trailing_zeros();
+
+    // Same bit-reversed order as the precomputed roots
+    let natural = compute_powers(&domain_generator(log2_size as usize), size);
+    let roots_of_unity = (0..size)
+        .map(|i| {
+            natural[i
+                .reverse_bits()
+                .checked_shr(usize::BITS - log2_size)
+                .unwrap_or(0)]
+        })
+        .collect::<Vec<_>>();
+
+    // Lagrange basis at tau: L_i(tau) = w_i / n * (tau^n - 1) / (tau - w_i)
+    let vanishing = tau.pow_vartime(&[size as u64, 0, 0, 0]) - Scalar::one();
+    let size_inv = Scalar::from(size as u64).invert().unwrap();
+    let g1_points = roots_of_unity
+        .iter()
+        .map(|root| {
+            let lagrange = if vanishing == Scalar::zero() {
+                // tau is itself a domain point, where the basis is an indicator
+                Scalar::from(u64::from(tau == *root))
+            } else {
+                root * size_inv * vanishing * (tau - root).invert().unwrap()
+            };
+            G1Affine::from(G1Affine::generator() * lagrange)
+        })
+        .collect::<Vec<_>>();
+
+    let g2_points = compute_powers(&tau, NUM_G2_POINTS)
+        .iter()
+        .map(|power| G2Affine::from(G2Affine::generator() * power))
+        .collect::<Vec<_>>();
+
+    let prepared_tau_g2 = prepare_tau_g2(g2_points[1]);
+    KzgSettings {
+        roots_of_unity: roots_of_unity.leak(),
+        g1_points: g1_points.leak(),
+        g2_points: g2_points.leak(),
+        field_element_endianness: Endianness::Big,
+        subgroup_policy: SubgroupPolicy::Reject,
+        fiat_shamir_domain: DEFAULT_FIAT_SHAMIR_DOMAIN,
+        batch_domain: DEFAULT_BATCH_DOMAIN,
+        validated: false,
+        msm_window: None,
+        prepared_tau_g2: Some(prepared_tau_g2),
+    }
+}
+
+/// Decompresses the G1 point at `index` of a setup file, which must be in the subgroup
+fn g1_from_setup_bytes(index: usize, bytes: &[u8]) -> Result<G1Affine, KzgError> {
+    let bytes: [u8; BYTES_PER_G1_POINT] = bytes.try_into().map_err(|_| {
+        KzgError::InvalidBytesLength(format!(
+            "G1 point {} must be {} bytes, got {}",
+            index,
+            BYTES_PER_G1_POINT,
+            bytes.len()
+        ))
+    })?;
+    let point: G1Affine =
+        Option::from(G1Affine::from_compressed_unchecked(&bytes)).ok_or_else(|| {
+            KzgError::InvalidTrustedSetup(format!("G1 point {} is not on the curve", index))
+        })?;
+    if !bool::from(point.is_torsion_free()) {
+        return Err(KzgError::NotInSubgroup(format!(
+            "G1 point {} of the setup is not in the prime-order subgroup",
+            index
+        )));
+    }
+    Ok(point)
+}
+
+/// Decompresses the G2 point at `index` of a setup file, which must be in the subgroup
+fn g2_from_setup_point(index: usize, bytes: &[u8]) -> Result<G2Affine, KzgError> {
+    let point = g2_from_setup_bytes(bytes).ok_or_else(|| {
+        KzgError::InvalidTrustedSetup(format!("G2 point {} is not on the curve", index))
+    })?;
+    if !bool::from(point.is_torsion_free()) {
+        return Err(KzgError::NotInSubgroup(format!(
+            "G2 point {} of the setup is not in the prime-order subgroup",
+            index
+        )));
+    }
+    Ok(point)
+}
+
+/// Turns the checks of the point at `index` of a setup into the matching error
+fn check_setup_point(
+    group: &str,
+    index: usize,
+    on_curve: Choice,
+    torsion_free: Choice,
+) -> Result<(), KzgError> {
+    if !bool::from(on_curve) {
+        return Err(KzgError::NotOnCurve(format!(
+            "{} point {} of the setup is not on the curve",
+            group, index
+        )));
+    }
+    if !bool::from(torsion_free) {
+        return Err(KzgError::NotInSubgroup(format!(
+            "{} point {} of the setup is not in the prime-order subgroup",
+            group, index
+        )));
+    }
+    Ok(())
+}
+
 pub fn get_kzg_settings() -> KzgSettings {
     KzgSettings {
         roots_of_unity: get_roots_of_unity(),
         g1_points: get_g1_points(),
         g2_points: get_g2_points(),
+        field_element_endianness: Endianness::Big,
+        subgroup_policy: SubgroupPolicy::Reject,
+        fiat_shamir_domain: DEFAULT_FIAT_SHAMIR_DOMAIN,
+        batch_domain: DEFAULT_BATCH_DOMAIN,
+        validated: false,
+        msm_window: None,
+        prepared_tau_g2: Some(prepare_tau_g2(get_g2_points()[1])),
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone)]
 #[repr(C, align(4))]
 pub struct KzgSettings {
     pub roots_of_unity: &'static [Scalar],
     pub g1_points: &'static [G1Affine],
+    /// Monomial G2 points `[tau^i]G2`. EIP-4844 only reads the first two, but extended setups
+    /// may carry more than the [`NUM_G2_POINTS`] of the mainnet ceremony.
     pub g2_points: &'static [G2Affine],
+    /// Byte order of the field elements inside a blob. Must stay [`Endianness::Big`] for
+    /// EIP-4844; little-endian is only meant for non-standard deployments.
+    pub field_element_endianness: Endianness,
+    /// How commitments and proofs outside the G1 subgroup are handled. Must stay
+    /// [`SubgroupPolicy::Reject`] outside of research code, see [`SubgroupPolicy::ClearCofactor`].
+    pub subgroup_policy: SubgroupPolicy,
+    /// Domain separator of the Fiat-Shamir challenge of a blob. Must stay
+    /// [`FIAT_SHAMIR_PROTOCOL_DOMAIN`] for EIP-4844, other values are for forked protocols.
+    pub fiat_shamir_domain: [u8; DOMAIN_STR_LENGTH],
+    /// Domain separator of the batch verification randomness, [`RANDOM_CHALLENGE_KZG_BATCH_DOMAIN`]
+    /// for EIP-4844
+    pub batch_domain: [u8; DOMAIN_STR_LENGTH],
+    /// Whether the points were checked, see [`KzgSettings::is_validated`]. Private, so only the
+    /// checks can set it.
+    validated: bool,
+    /// Bucket width of the MSM of a commitment, see [`KzgSettingsBuilder::msm_window`]
+    msm_window: Option<u8>,
+    /// `[tau]G2` prepared once when the settings are built, so verifying a proof does not
+    /// allocate. `None` when turned off with [`KzgSettingsBuilder::precompute`].
+    prepared_tau_g2: Option<Arc<PreparedTauG2>>,
 }
 
+/// Compares the setups, not the values prepared from them, whether they were validated nor the
+/// tuning of the computations
+impl PartialEq for KzgSettings {
+    fn eq(&self, other: &Self) -> bool {
+        self.roots_of_unity == other.roots_of_unity
+            && self.g1_points == other.g1_points
+            && self.g2_points == other.g2_points
+            && self.field_element_endianness == other.field_element_endianness
+            && self.subgroup_policy == other.subgroup_policy
+            && self.fiat_shamir_domain == other.fiat_shamir_domain
+            && self.batch_domain == other.batch_domain
+    }
+}
+
+impl Eq for KzgSettings {}
+
 #[derive(Debug, Clone, Default, Eq)]
 pub enum EnvKzgSettings {
     #[default]
@@ -91,8 +425,951 @@ impl EnvKzgSettings {
     }
 }
 
+/// Assembles a [`KzgSettings`] from its parts, checking they fit together on [`Self::build`]
+#[derive(Debug, Clone, Default)]
+pub struct KzgSettingsBuilder {
+    roots_of_unity: Option<&'static [Scalar]>,
+    g1_points: Option<&'static [G1Affine]>,
+    g2_points: Option<&'static [G2Affine]>,
+    field_element_endianness: Endianness,
+    subgroup_policy: SubgroupPolicy,
+    fiat_shamir_domain: Option<[u8; DOMAIN_STR_LENGTH]>,
+    batch_domain: Option<[u8; DOMAIN_STR_LENGTH]>,
+    precompute: Option<bool>,
+    msm_window: Option<u8>,
+}
+
+impl KzgSettingsBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Bit-reversed G1 points in Lagrange form, one per field element of a blob
+    pub fn g1_lagrange(mut self, g1_points: &'static [G1Affine]) -> Self {
+        self.g1_points = Some(g1_points);
+        self
+    }
+
+    /// G2 points in monomial form, at least `[1]G2` and `[tau]G2`
+    pub fn g2_monomial(mut self, g2_points: &'static [G2Affine]) -> Self {
+        self.g2_points = Some(g2_points);
+        self
+    }
+
+    /// Bit-reversed roots of unity of the domain. Defaults to the precomputed ones when the G1
+    /// points have the EIP-4844 size.
+    pub fn roots_of_unity(mut self, roots_of_unity: &'static [Scalar]) -> Self {
+        self.roots_of_unity = Some(roots_of_unity);
+        self
+    }
+
+    pub fn field_element_endianness(mut self, endianness: Endianness) -> Self {
+        self.field_element_endianness = endianness;
+        self
+    }
+
+    /// Defaults to [`SubgroupPolicy::Reject`], see [`SubgroupPolicy::ClearCofactor`] before
+    /// changing it
+    pub fn subgroup_policy(mut self, policy: SubgroupPolicy) -> Self {
+        self.subgroup_policy = policy;
+        self
+    }
+
+    /// Defaults to [`FIAT_SHAMIR_PROTOCOL_DOMAIN`], only forked protocols should change it
+    pub fn fiat_shamir_domain(mut self, domain: [u8; DOMAIN_STR_LENGTH]) -> Self {
+        self.fiat_shamir_domain = Some(domain);
+        self
+    }
+
+    /// Defaults to [`RANDOM_CHALLENGE_KZG_BATCH_DOMAIN`], only forked protocols should change it
+    pub fn batch_domain(mut self, domain: [u8; DOMAIN_STR_LENGTH]) -> Self {
+        self.batch_domain = Some(domain);
+        self
+    }
+
+    /// Whether to prepare `[tau]G2` for the Miller loop once, when building the settings, rather
+    /// than on every verification. Defaults to `true`, turning it off saves memory and build time
+    /// for settings that verify little.
+    pub fn precompute(mut self, precompute: bool) -> Self {
+        self.precompute = Some(precompute);
+        self
+    }
+
+    /// Bucket width in bits, from 1 to 16, of the Pippenger MSM behind a commitment. Defaults to
+    /// a width picked from the number of G1 points.
+    pub fn msm_window(mut self, window: u8) -> Self {
+        self.msm_window = Some(window);
+        self
+    }
+
+    pub fn build(self) -> Result<KzgSettings, KzgError> {
+        let g1_points = self
+            .g1_points
+            .ok_or_else(|| KzgError::BadArgs("Missing G1 points".to_string()))?;
+        let g2_points = self
+            .g2_points
+            .ok_or_else(|| KzgError::BadArgs("Missing G2 points".to_string()))?;
+
+        if !g1_points.len().is_power_of_two() {
+            return Err(KzgError::BadArgs(format!(
+                "The number of G1 points must be a power of two, got {}",
+                g1_points.len()
+            )));
+        }
+        if g2_points.len() < 2 {
+            return Err(KzgError::BadArgs(format!(
+                "At least 2 G2 points are needed, got {}",
+                g2_points.len()
+            )));
+        }
+
+        let roots_of_unity = match self.roots_of_unity {
+            Some(roots_of_unity) => roots_of_unity,
+            None if g1_points.len() == NUM_ROOTS_OF_UNITY => get_roots_of_unity(),
+            None => {
+                return Err(KzgError::BadArgs(
+                    "Missing roots of unity for a non-standard domain size".to_string(),
+                ))
+            }
+        };
+        if roots_of_unity.len() != g1_points.len() {
+            return Err(KzgError::BadArgs(format!(
+                "Expected {} roots of unity, got {}",
+                g1_points.len(),
+                roots_of_unity.len()
+            )));
+        }
+        if let Some(window) = self.msm_window.filter(|window| !(1..=16).contains(window)) {
+            return Err(KzgError::BadArgs(format!(
+                "The MSM window must be between 1 and 16 bits, got {}",
+                window
+            )));
+        }
+
+        Ok(KzgSettings {
+            roots_of_unity,
+            g1_points,
+            g2_points,
+            field_element_endianness: self.field_element_endianness,
+            subgroup_policy: self.subgroup_policy,
+            fiat_shamir_domain: self
+                .fiat_shamir_domain
+                .unwrap_or(DEFAULT_FIAT_SHAMIR_DOMAIN),
+            batch_domain: self.batch_domain.unwrap_or(DEFAULT_BATCH_DOMAIN),
+            validated: false,
+            msm_window: self.msm_window,
+            prepared_tau_g2: self
+                .precompute
+                .unwrap_or(true)
+                .then(|| prepare_tau_g2(g2_points[1])),
+        })
+    }
+}
+
 impl KzgSettings {
+    pub fn builder() -> KzgSettingsBuilder {
+        KzgSettingsBuilder::new()
+    }
+
+    /// Builds the settings of a custom EIP-4844 setup, e.g. for a testnet ceremony. The points are
+    /// leaked to get the `'static` slices held here, so this is meant to run once per process.
+    pub fn new(g1_points: Vec<G1Affine>, g2_points: Vec<G2Affine>) -> Result<Self, KzgError> {
+        if g1_points.len() != NUM_G1_POINTS {
+            return Err(KzgError::BadArgs(format!(
+                "Expected {} G1 points, got {}",
+                NUM_G1_POINTS,
+                g1_points.len()
+            )));
+        }
+        if g2_points.len() != NUM_G2_POINTS {
+            return Err(KzgError::BadArgs(format!(
+                "Expected {} G2 points, got {}",
+                NUM_G2_POINTS,
+                g2_points.len()
+            )));
+        }
+
+        Self::builder()
+            .g1_lagrange(g1_points.leak())
+            .g2_monomial(g2_points.leak())
+            .roots_of_unity(get_roots_of_unity())
+            .build()
+    }
+
+    /// The Ethereum mainnet setup compiled into the crate, shared by every caller. The points
+    /// are decompressed by `build.rs`, the instance itself is only assembled on first access.
+    #[cfg(feature = "embedded-setup")]
+    pub fn mainnet() -> &'static KzgSettings {
+        static MAINNET: Once<KzgSettings> = Once::new();
+        MAINNET.call_once(|| {
+            KzgSettings::load_trusted_setup_file().expect("failed to load the mainnet setup")
+        })
+    }
+
     pub fn load_trusted_setup_file() -> Result<Self, KzgError> {
-        Ok(get_kzg_settings())
+        Self::builder()
+            .g1_lagrange(get_g1_points())
+            .g2_monomial(get_g2_points())
+            .roots_of_unity(get_roots_of_unity())
+            .build()
+    }
+
+    /// Parses a setup in the c-kzg `.txt` format: the G1 and G2 point counts on the first two
+    /// lines, then one hex point per line, the Lagrange G1 points in natural order followed by
+    /// the monomial G2 points. The points are checked as by [`Self::validate`].
+    pub fn parse_trusted_setup(contents: &str) -> Result<Self, KzgError> {
+        let mut lines = contents
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty());
+        let mut parse_count = |name: &str| {
+            lines
+                .next()
+                .and_then(|line| line.parse::<usize>().ok())
+                .ok_or_else(|| {
+                    KzgError::InvalidTrustedSetup(format!("Missing the number of {} points", name))
+                })
+        };
+        let num_g1_points = parse_count("G1")?;
+        let num_g2_points = parse_count("G2")?;
+
+        let num_points = num_g1_points.checked_add(num_g2_points).ok_or_else(|| {
+            KzgError::InvalidTrustedSetup(format!(
+                "Too many points: {} G1 and {} G2",
+                num_g1_points, num_g2_points
+            ))
+        })?;
+
+        let lines = lines.collect::<Vec<_>>();
+        if lines.len() != num_points {
+            return Err(KzgError::InvalidTrustedSetup(format!(
+                "Expected {} points, got {} lines",
+                num_points,
+                lines.len()
+            )));
+        }
+        let decode = |index: usize| {
+            let line = lines
+                .get(index)
+                .ok_or_else(|| KzgError::InvalidTrustedSetup(format!("Missing point {}", index)))?;
+            hex::decode(line.strip_prefix("0x").unwrap_or(line)).map_err(|e| {
+                KzgError::InvalidHexFormat(format!("Bad hex on point {}: {}", index, e))
+            })
+        };
+
+        let g1_points = (0..num_g1_points)
+            .map(|i| g1_from_setup_bytes(i, &decode(i)?))
+            .collect::<Result<Vec<_>, _>>()?;
+        let g2_points = (num_g1_points..lines.len())
+            .map(|index| {
+                let i = index - num_g1_points;
+                let bytes = decode(index)?;
+                if bytes.len() != BYTES_PER_G2_POINT && bytes.len() != 2 * BYTES_PER_G2_POINT {
+                    return Err(KzgError::InvalidBytesLength(format!(
+                        "G2 point {} must be {} bytes, got {}",
+                        i,
+                        BYTES_PER_G2_POINT,
+                        bytes.len()
+                    )));
+                }
+                g2_from_setup_point(i, &bytes)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Self::from_setup_points(g1_points, g2_points)
+    }
+
+    /// Same as the c-kzg `load_trusted_setup`: takes the concatenated compressed Lagrange G1
+    /// points, in natural order, and monomial G2 points of the EIP-4844 setup, e.g. embedded
+    /// with `include_bytes!`. The points are checked as by [`Self::validate`].
+    pub fn load_trusted_setup(g1_bytes: &[u8], g2_bytes: &[u8]) -> Result<Self, KzgError> {
+        if g1_bytes.len() != NUM_G1_POINTS * BYTES_PER_G1_POINT {
+            return Err(KzgError::InvalidBytesLength(format!(
+                "Expected {} bytes of G1 points, got {}",
+                NUM_G1_POINTS * BYTES_PER_G1_POINT,
+                g1_bytes.len()
+            )));
+        }
+        if g2_bytes.len() != NUM_G2_POINTS * BYTES_PER_G2_POINT {
+            return Err(KzgError::InvalidBytesLength(format!(
+                "Expected {} bytes of G2 points, got {}",
+                NUM_G2_POINTS * BYTES_PER_G2_POINT,
+                g2_bytes.len()
+            )));
+        }
+
+        let g1_points = g1_bytes
+            .chunks_exact(BYTES_PER_G1_POINT)
+            .enumerate()
+            .map(|(i, bytes)| g1_from_setup_bytes(i, bytes))
+            .collect::<Result<Vec<_>, _>>()?;
+        let g2_points = g2_bytes
+            .chunks_exact(BYTES_PER_G2_POINT)
+            .enumerate()
+            .map(|(i, bytes)| g2_from_setup_point(i, bytes))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Self::from_setup_points(g1_points, g2_points)
+    }
+
+    /// Builds the settings from subgroup checked G1 points in the natural order of setup files,
+    /// leaking the points to get the `'static` slices held here, and checks they are consistent
+    fn from_setup_points(
+        g1_points: Vec<G1Affine>,
+        g2_points: Vec<G2Affine>,
+    ) -> Result<Self, KzgError> {
+        if !g1_points.len().is_power_of_two() {
+            return Err(KzgError::InvalidTrustedSetup(format!(
+                "The number of G1 points must be a power of two, got {}",
+                g1_points.len()
+            )));
+        }
+
+        // Stored bit-reversed, to line up with the roots of unity
+        let unused_bits = usize::BITS - g1_points.len().trailing_zeros();
+        let mut bit_reversed = vec![G1Affine::identity(); g1_points.len()];
+        for (i, point) in g1_points.into_iter().enumerate() {
+            bit_reversed[i.reverse_bits().checked_shr(unused_bits).unwrap_or(0)] = point;
+        }
+
+        let mut settings = Self::builder()
+            .g1_lagrange(bit_reversed.leak())
+            .g2_monomial(g2_points.leak())
+            .build()?;
+        settings.check_consistency()?;
+        settings.validated = true;
+        Ok(settings)
+    }
+
+    /// `[tau]G2` prepared for the Miller loop, or `None` if it was not precomputed or
+    /// `g2_points` no longer holds the point it was prepared from
+    pub(crate) fn prepared_tau_g2(&self) -> Option<&G2Prepared> {
+        self.prepared_tau_g2
+            .as_deref()
+            .filter(|prepared| self.g2_points.get(1) == Some(&prepared.tau_g2))
+            .map(|prepared| &prepared.prepared)
+    }
+
+    /// Bucket width of the MSM of a commitment, `None` when picked from the number of points
+    pub fn msm_window(&self) -> Option<u8> {
+        self.msm_window
+    }
+
+    /// [`Self::prepared_tau_g2`], or `[tau]G2` prepared on the spot when it is out of date
+    pub(crate) fn tau_g2_for_miller_loop(&self) -> Cow<'_, G2Prepared> {
+        match self.prepared_tau_g2() {
+            Some(prepared) => Cow::Borrowed(prepared),
+            None => Cow::Owned(G2Prepared::from(self.g2_points[1])),
+        }
+    }
+
+    /// Whether the points went through the checks of [`Self::validate`], either explicitly or in
+    /// [`Self::parse_trusted_setup`] and [`Self::load_trusted_setup`]. The built-in setup is
+    /// decoded without checks at build time, so it reports `false` until validated.
+    ///
+    /// Only the checked points are vouched for: writing to any field afterwards makes the flag
+    /// meaningless.
+    pub fn is_validated(&self) -> bool {
+        self.validated
+    }
+
+    /// Checks the points are in their prime-order subgroups and form a consistent setup: `G2`
+    /// first, Lagrange G1 points summing to `G1` and agreeing with `[tau]G2` on `tau`.
+    pub fn validate(mut self) -> Result<Self, KzgError> {
+        for (i, point) in self.g1_points.iter().enumerate() {
+            check_setup_point("G1", i, point.is_on_curve(), point.is_torsion_free())?;
+        }
+        for (i, point) in self.g2_points.iter().enumerate() {
+            check_setup_point("G2", i, point.is_on_curve(), point.is_torsion_free())?;
+        }
+
+        self.check_consistency()?;
+        self.validated = true;
+        Ok(self)
+    }
+
+    /// The checks of [`Self::validate`] past the subgroup checks
+    fn check_consistency(&self) -> Result<(), KzgError> {
+        if self.g2_points.len() < 2 || self.g2_points[0] != G2Affine::generator() {
+            return Err(KzgError::BadArgs(
+                "The G2 points do not start with the generator".to_string(),
+            ));
+        }
+        if self.roots_of_unity.len() != self.g1_points.len() {
+            return Err(KzgError::BadArgs(
+                "The roots of unity do not match the G1 points".to_string(),
+            ));
+        }
+
+        // The Lagrange basis interpolates 1 and X, so it must sum to G1 and weigh to [tau]G1
+        let g1 = self
+            .g1_points
+            .iter()
+            .map(G1Projective::from)
+            .sum::<G1Projective>();
+        let tau_g1 = g1_lincomb_with_window(self.g1_points, self.roots_of_unity, self.msm_window);
+        if g1 != G1Projective::generator()
+            || !pairings_verify(
+                tau_g1.into(),
+                G2Affine::generator(),
+                G1Affine::generator(),
+                self.g2_points[1],
+            )
+        {
+            return Err(KzgError::BadArgs(
+                "The G1 points are not a Lagrange basis for the G2 setup".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Number of field elements in a blob committed to with this setup, i.e. the domain size.
+    /// Polynomial-level functions follow it, but [`crate::Blob`] is still sized for EIP-4844.
+    pub fn field_elements_per_blob(&self) -> usize {
+        self.roots_of_unity.len()
+    }
+
+    /// Estimates the work of [`crate::KzgProof::verify_blob_kzg_proof_batch`] on `batch_size`
+    /// blobs, for schedulers weighing batching against verifying inline. This is arithmetic on
+    /// the batch and domain sizes, counted as the `op-counts` feature does, not a measurement.
+    pub fn estimate_verify_cost(&self, batch_size: usize) -> VerifyCost {
+        // Evaluating a blob: 3 multiplications per point and one inversion for the batch
+        // inversion, then 2 more per point for the barycentric sum
+        let field_ops_per_blob = 5 * self.field_elements_per_blob() + 1;
+
+        match batch_size {
+            0 => VerifyCost::default(),
+            // Checked on its own, with a single scalar multiplication for [y]_1
+            1 => VerifyCost {
+                pairings: 2,
+                g1_scalar_muls: 1,
+                field_ops: field_ops_per_blob,
+            },
+            // Four terms per entry in the aggregates, and r^i and r^i * z_i on top of evaluating
+            _ => VerifyCost {
+                pairings: 2,
+                g1_scalar_muls: 4 * batch_size,
+                field_ops: batch_size * (field_ops_per_blob + 2),
+            },
+        }
+    }
+
+    /// Returns the generator of the evaluation domain, sized by `roots_of_unity`
+    pub fn domain_generator(&self) -> Scalar {
+        domain_generator(self.field_elements_per_blob().trailing_zeros() as usize)
+    }
+
+    /// The evaluation domain in canonical big-endian bytes, in the same (bit-reversed) order as
+    /// `roots_of_unity`, for exporting it to other implementations
+    pub fn roots_of_unity_bytes(&self) -> Vec<Bytes32> {
+        self.roots_of_unity
+            .iter()
+            .map(|root| scalar_to_blob_bytes(root, Endianness::Big))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_roots_of_unity_bytes() {
+        let kzg_settings = KzgSettings::load_trusted_setup_file().unwrap();
+        let roots = kzg_settings.roots_of_unity_bytes();
+
+        assert_eq!(roots.len(), NUM_ROOTS_OF_UNITY);
+        let mut one = [0u8; 32];
+        one[31] = 1;
+        assert_eq!(roots[0].as_slice(), one);
+        // Bit-reversed order puts -1 second
+        assert_eq!(
+            roots[1].as_slice(),
+            scalar_to_blob_bytes(&-Scalar::one(), Endianness::Big).as_slice()
+        );
+    }
+
+    #[test]
+    fn test_estimate_verify_cost() {
+        let kzg_settings = KzgSettings::load_trusted_setup_file().unwrap();
+        assert_eq!(kzg_settings.estimate_verify_cost(0), VerifyCost::default());
+        assert_eq!(kzg_settings.estimate_verify_cost(1).pairings, 2);
+
+        // Past the single-entry shortcut every extra blob adds the same work, bar the pairings
+        let step = {
+            let (a, b) = (
+                kzg_settings.estimate_verify_cost(2),
+                kzg_settings.estimate_verify_cost(3),
+            );
+            (
+                b.g1_scalar_muls - a.g1_scalar_muls,
+                b.field_ops - a.field_ops,
+            )
+        };
+        assert!(step.0 > 0 && step.1 > 0);
+        for batch_size in [2, 10, 100, 4096] {
+            let cost = kzg_settings.estimate_verify_cost(batch_size);
+            assert_eq!(cost.pairings, 2);
+            assert_eq!(cost.g1_scalar_muls, step.0 * batch_size);
+            assert_eq!(cost.field_ops, step.1 * batch_size);
+        }
+    }
+
+    #[test]
+    fn test_typed_roots_of_unity() {
+        use crate::fft::{bit_reversal_permutation, expand_root_of_unity};
+
+        let roots = roots_of_unity();
+        assert!(core::ptr::eq(roots.as_slice(), get_roots_of_unity()));
+        assert_eq!(roots[0], Scalar::one());
+        assert_eq!(roots[1], -Scalar::one());
+
+        let log2_size = NUM_ROOTS_OF_UNITY.trailing_zeros() as usize;
+        let mut expanded =
+            expand_root_of_unity(&primitive_root_of_unity(log2_size), NUM_ROOTS_OF_UNITY).unwrap();
+        bit_reversal_permutation(&mut expanded);
+        assert_eq!(expanded, roots.as_slice());
+
+        for order_log2 in 0..SCALE2_ROOT_OF_UNITY.len() {
+            assert!(is_primitive_root_of_unity(
+                &primitive_root_of_unity(order_log2),
+                order_log2
+            ));
+        }
+    }
+
+    #[test]
+    fn test_verify_roots_of_unity_table() {
+        assert!(verify_roots_of_unity_table());
+
+        for (log2_order, limbs) in SCALE2_ROOT_OF_UNITY.iter().enumerate() {
+            let root = Scalar::from_raw(*limbs);
+            assert!(is_primitive_root_of_unity(&root, log2_order));
+            // A primitive 2^k-th root never has order 2^(k+1), and its square has order 2^(k-1)
+            assert!(!is_primitive_root_of_unity(&root, log2_order + 1));
+            if log2_order > 0 {
+                assert!(is_primitive_root_of_unity(&root.square(), log2_order - 1));
+            }
+        }
+    }
+
+    #[test]
+    fn test_g2_points_length() {
+        let settings = get_kzg_settings();
+        assert_eq!(settings.g2_points.len(), NUM_G2_POINTS);
+        assert_eq!(settings.g2_points[0], G2Affine::generator());
+    }
+
+    #[test]
+    fn test_kzg_settings_builder() {
+        use crate::kzg_proof::{
+            poly_to_kzg_commitment,
+            tests::{Input, Test},
+        };
+        use crate::{test_files::VERIFY_KZG_PROOF_TESTS, KzgProof};
+
+        let settings = KzgSettings::builder()
+            .g1_lagrange(get_g1_points())
+            .g2_monomial(get_g2_points())
+            .build()
+            .unwrap();
+        assert_eq!(settings, get_kzg_settings());
+
+        let (_test_file, data) = VERIFY_KZG_PROOF_TESTS
+            .iter()
+            .find(|(test_file, _)| test_file.starts_with("verify_kzg_proof_case_correct_proof"))
+            .unwrap();
+        let test: Test<Input> = serde_yaml::from_str(data).unwrap();
+
+        // The tuning changes how the checks are computed, not their outcome
+        let tuned = KzgSettings::builder()
+            .g1_lagrange(get_g1_points())
+            .g2_monomial(get_g2_points())
+            .precompute(false)
+            .msm_window(5)
+            .build()
+            .unwrap();
+        assert_eq!(tuned, settings);
+        assert_eq!(tuned.msm_window(), Some(5));
+        assert!(tuned.prepared_tau_g2().is_none());
+        assert_eq!(settings.msm_window(), None);
+        assert!(settings.prepared_tau_g2().is_some());
+        for settings in [&settings, &tuned] {
+            assert!(KzgProof::verify_kzg_proof(
+                &test.input.get_commitment().unwrap(),
+                &test.input.get_z().unwrap(),
+                &test.input.get_y().unwrap(),
+                &test.input.get_proof().unwrap(),
+                settings,
+            )
+            .unwrap());
+        }
+
+        let toy = generate_insecure_setup(Scalar::from(7), 16);
+        let polynomial = (1..=16).map(Scalar::from).collect::<Vec<_>>();
+        for window in [1, 3, 16] {
+            let windowed = KzgSettings::builder()
+                .g1_lagrange(toy.g1_points)
+                .g2_monomial(toy.g2_points)
+                .roots_of_unity(toy.roots_of_unity)
+                .msm_window(window)
+                .build()
+                .unwrap();
+            assert_eq!(
+                poly_to_kzg_commitment(&polynomial, &windowed).unwrap(),
+                poly_to_kzg_commitment(&polynomial, &toy).unwrap()
+            );
+        }
+
+        // Incomplete or inconsistent parts are rejected
+        for window in [0, 17] {
+            assert!(matches!(
+                KzgSettings::builder()
+                    .g1_lagrange(get_g1_points())
+                    .g2_monomial(get_g2_points())
+                    .msm_window(window)
+                    .build(),
+                Err(KzgError::BadArgs(_))
+            ));
+        }
+        assert!(KzgSettings::builder()
+            .g1_lagrange(get_g1_points())
+            .build()
+            .is_err());
+        assert!(KzgSettings::builder()
+            .g1_lagrange(&get_g1_points()[..1024])
+            .g2_monomial(get_g2_points())
+            .build()
+            .is_err());
+        assert!(KzgSettings::builder()
+            .g1_lagrange(get_g1_points())
+            .g2_monomial(&get_g2_points()[..1])
+            .build()
+            .is_err());
+        assert!(KzgSettings::builder()
+            .g1_lagrange(&get_g1_points()[..1024])
+            .g2_monomial(get_g2_points())
+            .roots_of_unity(get_roots_of_unity())
+            .build()
+            .is_err());
+    }
+
+    #[test]
+    fn test_domain_size_mismatch_is_rejected() {
+        use crate::{blob_to_kzg_commitment, dtypes::Blob};
+
+        // A domain that does not match the G1 points never makes it into a `KzgSettings`
+        for roots in [&get_roots_of_unity()[..16], get_roots_of_unity()] {
+            assert!(KzgSettings::builder()
+                .g1_lagrange(&get_g1_points()[..roots.len() / 2])
+                .g2_monomial(get_g2_points())
+                .roots_of_unity(roots)
+                .build()
+                .is_err());
+        }
+
+        // Smaller domains are fine for polynomials, but blobs are rejected instead of misread
+        let toy = generate_insecure_setup(Scalar::from(7), 16);
+        assert_eq!(toy.field_elements_per_blob(), 16);
+        let blob = Blob::zero();
+        assert!(blob.to_evaluations(&toy).is_err());
+        assert!(matches!(
+            blob_to_kzg_commitment(&blob, &toy),
+            Err(KzgError::MismatchedLengths {
+                expected: 16,
+                got: crate::NUM_FIELD_ELEMENTS_PER_BLOB
+            })
+        ));
+    }
+
+    #[test]
+    fn test_kzg_settings_new() {
+        let g1_points = vec![G1Affine::generator(); NUM_G1_POINTS];
+        let g2_points = vec![G2Affine::generator(); NUM_G2_POINTS];
+
+        let settings = KzgSettings::new(g1_points.clone(), g2_points.clone()).unwrap();
+        assert_eq!(settings.g1_points, g1_points.as_slice());
+        assert_eq!(settings.g2_points, g2_points.as_slice());
+        assert_eq!(settings.roots_of_unity, get_roots_of_unity());
+        assert!(!settings.is_validated());
+
+        assert!(matches!(
+            KzgSettings::new(g1_points[..NUM_G1_POINTS / 2].to_vec(), g2_points.clone()),
+            Err(KzgError::BadArgs(_))
+        ));
+        assert!(matches!(
+            KzgSettings::new(g1_points, g2_points[..2].to_vec()),
+            Err(KzgError::BadArgs(_))
+        ));
+    }
+
+    #[test]
+    fn test_parse_trusted_setup() {
+        let trusted_setup = include_str!("trusted_setup.txt");
+        let settings = KzgSettings::parse_trusted_setup(trusted_setup).unwrap();
+        assert_eq!(settings, get_kzg_settings());
+
+        let lines = trusted_setup.lines().collect::<Vec<_>>();
+        let with_lines = |replace: &dyn Fn(usize, &str) -> String| {
+            let contents = lines
+                .iter()
+                .enumerate()
+                .map(|(i, line)| replace(i, line))
+                .collect::<Vec<_>>()
+                .join("\n");
+            KzgSettings::parse_trusted_setup(&contents)
+        };
+
+        assert!(matches!(
+            with_lines(&|i, line| if i == 2 {
+                "zz".repeat(48)
+            } else {
+                line.to_string()
+            }),
+            Err(KzgError::InvalidHexFormat(_))
+        ));
+        assert!(matches!(
+            with_lines(&|i, line| if i == 3 {
+                line[2..].to_string()
+            } else {
+                line.to_string()
+            }),
+            Err(KzgError::InvalidBytesLength(_))
+        ));
+        assert!(matches!(
+            with_lines(&|i, line| if i == 0 {
+                "4095".to_string()
+            } else {
+                line.to_string()
+            }),
+            Err(KzgError::InvalidTrustedSetup(_))
+        ));
+        assert!(matches!(
+            KzgSettings::parse_trusted_setup(&lines[..lines.len() - 1].join("\n")),
+            Err(KzgError::InvalidTrustedSetup(_))
+        ));
+
+        // Counts whose sum overflows, with or without a point that would then be indexed
+        for contents in [
+            "18446744073709551615\n1\n".to_string(),
+            format!("18446744073709551615\n2\n{}", lines[2]),
+        ] {
+            assert!(matches!(
+                KzgSettings::parse_trusted_setup(&contents),
+                Err(KzgError::InvalidTrustedSetup(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_load_trusted_setup() {
+        let trusted_setup = include_str!("trusted_setup.txt");
+        let lines = trusted_setup.lines().skip(2).collect::<Vec<_>>();
+        let g1_bytes = hex::decode(lines[..NUM_G1_POINTS].concat()).unwrap();
+        let g2_bytes = hex::decode(lines[NUM_G1_POINTS..].concat()).unwrap();
+
+        let settings = KzgSettings::load_trusted_setup(&g1_bytes, &g2_bytes).unwrap();
+        assert_eq!(settings, get_kzg_settings());
+
+        assert!(matches!(
+            KzgSettings::load_trusted_setup(&g1_bytes[1..], &g2_bytes),
+            Err(KzgError::InvalidBytesLength(message)) if message.ends_with("196607")
+        ));
+        assert!(matches!(
+            KzgSettings::load_trusted_setup(&g1_bytes, &g2_bytes[..BYTES_PER_G2_POINT]),
+            Err(KzgError::InvalidBytesLength(message)) if message.ends_with("96")
+        ));
+
+        let mut off_curve = g1_bytes.clone();
+        off_curve[..BYTES_PER_G1_POINT].fill(0);
+        off_curve[0] = 0x80;
+        off_curve[BYTES_PER_G1_POINT - 1] = 1;
+        assert!(matches!(
+            KzgSettings::load_trusted_setup(&off_curve, &g2_bytes),
+            Err(KzgError::InvalidTrustedSetup(_))
+        ));
+
+        // Points on the curve but outside the subgroup are rejected as well
+        let mut not_in_subgroup = g1_bytes.clone();
+        not_in_subgroup[5 * BYTES_PER_G1_POINT..6 * BYTES_PER_G1_POINT].copy_from_slice(
+            &hex::decode("8123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef").unwrap(),
+        );
+        assert!(matches!(
+            KzgSettings::load_trusted_setup(&not_in_subgroup, &g2_bytes),
+            Err(KzgError::NotInSubgroup(message)) if message.starts_with("G1 point 5 ")
+        ));
+
+        // Almost no G2 point on the curve is in the subgroup, take the first one found
+        let g2_not_in_subgroup = (1..=u8::MAX)
+            .map(|x| {
+                let mut bytes = [0u8; BYTES_PER_G2_POINT];
+                bytes[0] = 0x80;
+                bytes[BYTES_PER_G2_POINT - 1] = x;
+                bytes
+            })
+            .find(|bytes| bool::from(G2Affine::from_compressed_unchecked(bytes).is_some()))
+            .unwrap();
+        assert!(bool::from(
+            G2Affine::from_compressed(&g2_not_in_subgroup).is_none()
+        ));
+        let mut not_in_subgroup = g2_bytes.clone();
+        not_in_subgroup[BYTES_PER_G2_POINT..2 * BYTES_PER_G2_POINT]
+            .copy_from_slice(&g2_not_in_subgroup);
+        assert!(matches!(
+            KzgSettings::load_trusted_setup(&g1_bytes, &not_in_subgroup),
+            Err(KzgError::NotInSubgroup(message)) if message.starts_with("G2 point 1 ")
+        ));
+    }
+
+    #[test]
+    #[cfg(feature = "embedded-setup")]
+    fn test_mainnet() {
+        let mainnet = KzgSettings::mainnet();
+        assert!(core::ptr::eq(mainnet, KzgSettings::mainnet()));
+        assert_eq!(*mainnet, get_kzg_settings());
+    }
+
+    #[test]
+    fn test_generate_insecure_setup() {
+        use crate::kzg_proof::{
+            compute_kzg_proof_impl, poly_to_kzg_commitment, verify_kzg_proof_impl,
+        };
+
+        let tau = Scalar::from(0x5eed);
+        let settings = generate_insecure_setup(tau, 16);
+        assert_eq!(settings.field_elements_per_blob(), 16);
+        assert_eq!(settings.g2_points.len(), NUM_G2_POINTS);
+        assert_eq!(settings.roots_of_unity[8], settings.domain_generator());
+        assert_eq!(settings.roots_of_unity[1], -Scalar::one());
+
+        let polynomial = (1..=16).map(Scalar::from).collect::<Vec<_>>();
+        let commitment = poly_to_kzg_commitment(&polynomial, &settings).unwrap();
+        for z in [Scalar::from(77), settings.roots_of_unity[5]] {
+            let (proof, y) = compute_kzg_proof_impl(&polynomial, z, &settings).unwrap();
+            assert!(verify_kzg_proof_impl(commitment, z, y, proof, &settings).unwrap());
+            assert!(
+                !verify_kzg_proof_impl(commitment, z, y + Scalar::one(), proof, &settings).unwrap()
+            );
+        }
+
+        // Deterministic in the secret
+        assert_eq!(generate_insecure_setup(tau, 16), settings);
+
+        // A secret on the domain still gives a working setup
+        let settings = generate_insecure_setup(settings.roots_of_unity[3], 16);
+        let commitment = poly_to_kzg_commitment(&polynomial, &settings).unwrap();
+        let (proof, y) = compute_kzg_proof_impl(&polynomial, Scalar::from(2), &settings).unwrap();
+        assert!(verify_kzg_proof_impl(commitment, Scalar::from(2), y, proof, &settings).unwrap());
+    }
+
+    #[test]
+    fn test_prepared_tau_g2() {
+        use crate::kzg_proof::{
+            compute_kzg_proof_impl, poly_to_kzg_commitment, verify_kzg_proof_impl,
+        };
+
+        let settings = get_kzg_settings();
+        assert!(Arc::ptr_eq(
+            settings.prepared_tau_g2.as_ref().unwrap(),
+            KzgSettings::load_trusted_setup_file()
+                .unwrap()
+                .prepared_tau_g2
+                .as_ref()
+                .unwrap()
+        ));
+        assert!(settings.prepared_tau_g2().is_some());
+
+        // Points written over the public fields are never checked against the stale preparation
+        let other = generate_insecure_setup(Scalar::from(8), 16);
+        let mut settings = generate_insecure_setup(Scalar::from(7), 16);
+        settings.g1_points = other.g1_points;
+        settings.g2_points = other.g2_points;
+        assert!(settings.prepared_tau_g2().is_none());
+
+        let polynomial = (1..=16).map(Scalar::from).collect::<Vec<_>>();
+        let commitment = poly_to_kzg_commitment(&polynomial, &other).unwrap();
+        let (proof, y) = compute_kzg_proof_impl(&polynomial, Scalar::from(3), &other).unwrap();
+        assert!(verify_kzg_proof_impl(commitment, Scalar::from(3), y, proof, &settings).unwrap());
+    }
+
+    #[test]
+    fn test_is_validated() {
+        let settings = KzgSettings::load_trusted_setup_file().unwrap();
+        assert!(!settings.is_validated());
+        let validated = settings.clone().validate().unwrap();
+        assert!(validated.is_validated());
+        assert_eq!(validated, settings);
+
+        // The checked loaders run the same checks
+        let trusted_setup = include_str!("trusted_setup.txt");
+        let parsed = KzgSettings::parse_trusted_setup(trusted_setup).unwrap();
+        assert!(parsed.is_validated());
+        let lines = trusted_setup.lines().skip(2).collect::<Vec<_>>();
+        let loaded = KzgSettings::load_trusted_setup(
+            &hex::decode(lines[..NUM_G1_POINTS].concat()).unwrap(),
+            &hex::decode(lines[NUM_G1_POINTS..].concat()).unwrap(),
+        )
+        .unwrap();
+        assert!(loaded.is_validated());
+        assert!(
+            !KzgSettings::new(parsed.g1_points.to_vec(), parsed.g2_points.to_vec())
+                .unwrap()
+                .is_validated()
+        );
+
+        let toy = generate_insecure_setup(Scalar::from(7), 16);
+        assert!(!toy.is_validated());
+        assert!(toy.clone().validate().unwrap().is_validated());
+
+        // A [tau]G2 from another secret, and G1 points out of order, are both caught
+        let other = generate_insecure_setup(Scalar::from(8), 16);
+        let mismatched = KzgSettings::builder()
+            .g1_lagrange(toy.g1_points)
+            .g2_monomial(other.g2_points)
+            .roots_of_unity(toy.roots_of_unity)
+            .build()
+            .unwrap();
+        assert!(mismatched.validate().is_err());
+        let mut shuffled = toy.g1_points.to_vec();
+        shuffled.swap(1, 2);
+        let shuffled = KzgSettings::builder()
+            .g1_lagrange(shuffled.leak())
+            .g2_monomial(toy.g2_points)
+            .roots_of_unity(toy.roots_of_unity)
+            .build()
+            .unwrap();
+        assert!(shuffled.validate().is_err());
+
+        // Same error as the loaders for a point outside the subgroup
+        let mut not_in_subgroup = toy.g1_points.to_vec();
+        not_in_subgroup[3] = G1Affine::from_compressed_unchecked(
+            &hex::decode("8123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef").unwrap().try_into().unwrap(),
+        )
+        .unwrap();
+        let not_in_subgroup = KzgSettings::builder()
+            .g1_lagrange(not_in_subgroup.leak())
+            .g2_monomial(toy.g2_points)
+            .roots_of_unity(toy.roots_of_unity)
+            .build()
+            .unwrap();
+        assert!(matches!(
+            not_in_subgroup.validate(),
+            Err(KzgError::NotInSubgroup(message)) if message.starts_with("G1 point 3 ")
+        ));
+    }
+
+    #[test]
+    fn test_domain_generator() {
+        let settings = get_kzg_settings();
+        let generator = settings.domain_generator();
+
+        assert_eq!(generator, domain_generator(12));
+        assert!(is_primitive_root_of_unity(&generator, 12));
+        assert_eq!(
+            generator.pow_vartime(&[NUM_ROOTS_OF_UNITY as u64, 0, 0, 0]),
+            Scalar::one()
+        );
+        // The stored roots are bit-reversed, so the generator sits at bitrev(1) = 2048
+        assert_eq!(settings.roots_of_unity[NUM_ROOTS_OF_UNITY / 2], generator);
     }
 }